@@ -28,6 +28,13 @@ use syn::{
 // fn case<'input>(input: Input<'input, Stream<Token>, Extras>) -> IResult<'input, Stream<Token>, Extras, Case> {}
 // ```
 
+/// Turns a function into a parser.
+///
+/// Accepted arguments:
+/// - `extras = Type` sets the `ParserExtras` of the parser (`aott::extra::Err<Input>` by default)
+/// - `noinline` removes the `#[inline(always)]` attribute from the function
+/// - `label` labels the parser with the function name (see `Parser::labelled`), and `label = "..."` with the given string.
+///   The error of the parser must implement `LabelError<Input, aott::error::Filtering>`.
 #[proc_macro_attribute]
 pub fn parser(args: TS, ts: TS) -> TS {
         let ts: TokenStream = ts.into();
@@ -51,7 +58,8 @@ fn parser_impl(args: TokenStream, ts: TokenStream) -> Result<TokenStream, syn::E
         let meta: Punctuated<Meta, Comma> = Punctuated::parse_terminated.parse2(args)?;
         let mut f = ItemFn::parse.parse2(ts)?;
         let mut inline = true;
-        let extras = meta.iter().find_map(|meth| match meth {
+        let mut label = None;
+        let extras = meta.iter().filter_map(|meth| match meth {
                 Meta::NameValue(MetaNameValue {
                         path,
                         value: Expr::Path(ExprPath { path: ext, .. }),
@@ -63,6 +71,21 @@ fn parser_impl(args: TokenStream, ts: TokenStream) -> Result<TokenStream, syn::E
                         inline = false;
                         None
                 }
+                Meta::Path(path) if path.is_ident(&Ident::new("label", Span::call_site())) => {
+                        label = Some(f.sig.ident.to_string());
+                        None
+                }
+                Meta::NameValue(MetaNameValue {
+                        path,
+                        value:
+                                Expr::Lit(ExprLit {
+                                        lit: Lit::Str(lit), ..
+                                }),
+                        ..
+                }) if path.is_ident(&Ident::new("label", Span::call_site())) => {
+                        label = Some(lit.value());
+                        None
+                }
                 Meta::NameValue(MetaNameValue {
                         path,
                         value:
@@ -74,7 +97,7 @@ fn parser_impl(args: TokenStream, ts: TokenStream) -> Result<TokenStream, syn::E
                         Some(lit.value().to_owned())
                 }
                 _ => None,
-        });
+        }).last();
         let mut lifetimes = vec![];
         let lifetime = Lifetime::new("'a", Span::call_site());
         let mut inputs = vec![];
@@ -148,6 +171,30 @@ fn parser_impl(args: TokenStream, ts: TokenStream) -> Result<TokenStream, syn::E
                                 bounds: Punctuated::new(),
                         })
                 }));
+        if let Some(label) = label {
+                // run the body in a closure, so that `?` and `return` inside of it still produce the error we relabel
+                let input = match f.sig.inputs.first() {
+                        Some(FnArg::Typed(pat)) => pat.pat.clone(),
+                        _ => return Err(syn::Error::new_spanned(&f.sig, "a parser must take an input")),
+                };
+                let ReturnType::Type(_, ret) = &f.sig.output else {
+                        unreachable!("the return type is always set above")
+                };
+                let block = &f.block;
+                f.block = Box::new(syn::parse_quote!({
+                        let __aott_before = #input.save();
+                        #[allow(clippy::redundant_closure_call)]
+                        let __aott_result = (|| -> #ret #block)();
+                        __aott_result.map_err(|error| {
+                                ::aott::primitive::relabel(
+                                        #input,
+                                        __aott_before,
+                                        ::aott::error::Filtering(::std::borrow::Cow::Borrowed(#label)),
+                                        error,
+                                )
+                        })
+                }));
+        }
         let inl = inline.then(|| quote!(#[inline(always)]));
         Ok(quote! {
             #inl
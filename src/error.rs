@@ -1,5 +1,7 @@
 use alloc::borrow::Cow;
 
+use std::fmt::Debug;
+
 use crate::{input::InputType, parser::ParserExtras};
 
 pub trait Error<I: InputType>: Sized {
@@ -22,13 +24,67 @@ pub trait Error<I: InputType>: Sized {
                         None => Self::unexpected_eof(span, Some(expected)),
                 }
         }
+
+        /// Merge this error with `other`, the error of another alternative that failed at the same place.
+        ///
+        /// This is used by [`Parser::or`] and [`choice`] to combine what all of the alternatives expected,
+        /// instead of just reporting the error of the last one.
+        /// The default implementation keeps `self` and discards `other`.
+        ///
+        /// [`Parser::or`]: crate::parser::Parser::or
+        /// [`choice`]: crate::primitive::choice
+        #[must_use]
+        fn merge(self, other: Self) -> Self {
+                let _ = other;
+                self
+        }
+
+        /// Add a context frame to this error.
+        ///
+        /// [`Parser::in_context`] calls this when an error propagates out of the parser it wraps,
+        /// so nested frames are received innermost first.
+        /// The default implementation discards the frame.
+        ///
+        /// [`Parser::in_context`]: crate::parser::Parser::in_context
+        #[must_use]
+        fn in_context(self, frame: Frame<I>) -> Self {
+                let _ = frame;
+                self
+        }
+
+        /// The tokens this error says were expected, as passed to [`Error::expected_token_found`]
+        /// or [`Error::unexpected_eof`].
+        ///
+        /// [`repair`] uses these to decide which tokens to try inserting.
+        /// The default implementation returns none, so only deleting the offending token is tried.
+        ///
+        /// [`repair`]: crate::recovery::repair
+        fn expected_tokens(&self) -> Vec<&I::Token> {
+                vec![]
+        }
+
+        /// Attach a suggestion of how to fix the input, like the ones found by [`repair`],
+        /// or a keyword in place of a misspelling of it (see [`text::unicode::keyword`]).
+        /// The default implementation discards the suggestion, and so does [`Simple`];
+        /// use [`Rich`] to keep them.
+        ///
+        /// [`text::unicode::keyword`]: crate::text::unicode::keyword
+        /// [`Simple`]: crate::extra::Simple
+        /// [`Rich`]: crate::extra::Rich
+        ///
+        /// [`repair`]: crate::recovery::repair
+        #[must_use]
+        fn suggest(self, suggestion: Suggestion<I::Span, I::Token>) -> Self {
+                let _ = suggestion;
+                self
+        }
 }
 
 pub trait LabelError<I: InputType, L>: Sized {
         fn from_label(span: I::Span, label: L, last_token: Option<I::Token>) -> Self;
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Located<L, E> {
         pub pos: L,
         pub err: E,
@@ -41,8 +97,194 @@ impl<L, E> Located<L, E> {
         }
 }
 
+/// How severe a [`Secondary`] error is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, derive_more::Display)]
+pub enum Severity {
+        /// Something is wrong with the input, but the parser recovered from it.
+        #[display(fmt = "error")]
+        Error,
+        /// The input is valid, but probably not what was intended.
+        #[display(fmt = "warning")]
+        Warning,
+        /// Additional information about the input.
+        #[display(fmt = "note")]
+        Note,
+}
+
+/// An error that did not make the parser fail, emitted by a [recovery strategy](crate::recovery)
+/// or [`Parser::validate`](crate::parser::Parser::validate).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Secondary<S, E> {
+        pub span: S,
+        pub severity: Severity,
+        pub err: E,
+}
+
+/// A fix for an error: an edit of the input that makes it parse, which editors can apply as a quick fix.
+/// See [`Error::suggest`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Suggestion<S, T> {
+        /// Insert `token` at `span`, which is empty.
+        Insert { span: S, token: T },
+        /// Delete `token`, which is at `span`.
+        Delete { span: S, token: T },
+        /// Replace whatever is at `span` with the text `with`, like a misspelled keyword with the keyword.
+        Replace { span: S, with: String },
+}
+
+impl<S, T> Suggestion<S, T> {
+        /// Where the edit is made.
+        pub fn span(&self) -> &S {
+                match self {
+                        Self::Insert { span, .. }
+                        | Self::Delete { span, .. }
+                        | Self::Replace { span, .. } => span,
+                }
+        }
+
+        /// Converts the span of this suggestion with `f`.
+        pub fn map_span<S2>(self, f: impl FnOnce(S) -> S2) -> Suggestion<S2, T> {
+                match self {
+                        Self::Insert { span, token } => Suggestion::Insert {
+                                span: f(span),
+                                token,
+                        },
+                        Self::Delete { span, token } => Suggestion::Delete {
+                                span: f(span),
+                                token,
+                        },
+                        Self::Replace { span, with } => Suggestion::Replace {
+                                span: f(span),
+                                with,
+                        },
+                }
+        }
+}
+
+impl<S, T: Debug> std::fmt::Display for Suggestion<S, T> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                match self {
+                        Self::Insert { token, .. } => write!(f, "insert {token:?}"),
+                        Self::Delete { token, .. } => write!(f, "remove {token:?}"),
+                        Self::Replace { with, .. } => write!(f, "did you mean `{with}`?"),
+                }
+        }
+}
+
+/// A context frame: a label naming what was being parsed, located at the span from where parsing it started
+/// up to where it failed.
+/// See [`Parser::in_context`](crate::parser::Parser::in_context).
+pub type Frame<I> = Located<<I as InputType>::Span, Cow<'static, str>>;
+
 pub type PResult<I, O, E> = Result<O, <E as ParserExtras<I>>::Error>;
 
+/// The output, the secondary errors and the fatal error of a [`ParseResult`], see [`ParseResult::into_parts`].
+pub type ParseParts<I, O, E> = (
+        Option<O>,
+        Vec<Secondary<<I as InputType>::Span, <E as ParserExtras<I>>::Error>>,
+        Option<<E as ParserExtras<I>>::Error>,
+);
+
+/// The result of [`Parser::parse_recovering`]: an output, if the parser succeeded,
+/// the secondary errors it recovered from (see [`recovery`](crate::recovery) and [`Parser::validate`]),
+/// and the fatal error, if the parser failed.
+///
+/// [`Parser::parse_recovering`]: crate::parser::Parser::parse_recovering
+/// [`Parser::validate`]: crate::parser::Parser::validate
+pub struct ParseResult<I: InputType, O, E: ParserExtras<I>> {
+        output: Option<O>,
+        secondary: Vec<Secondary<I::Span, E::Error>>,
+        error: Option<E::Error>,
+}
+
+impl<I: InputType, O, E: ParserExtras<I>> ParseResult<I, O, E> {
+        pub(crate) fn new(
+                result: PResult<I, O, E>,
+                secondary: Vec<Secondary<I::Span, E::Error>>,
+        ) -> Self {
+                let (output, error) = match result {
+                        Ok(output) => (Some(output), None),
+                        Err(error) => (None, Some(error)),
+                };
+                Self {
+                        output,
+                        secondary,
+                        error,
+                }
+        }
+
+        /// The output of the parser, or `None` if it failed.
+        pub fn output(&self) -> Option<&O> {
+                self.output.as_ref()
+        }
+
+        /// The secondary errors the parser recovered from, in the order they were emitted.
+        pub fn secondary(&self) -> &[Secondary<I::Span, E::Error>] {
+                &self.secondary
+        }
+
+        /// The error the parser failed with, or `None` if it succeeded.
+        pub fn error(&self) -> Option<&E::Error> {
+                self.error.as_ref()
+        }
+
+        /// Whether the parser failed, or emitted any secondary errors of [`Severity::Error`].
+        /// Warnings and notes are not errors.
+        pub fn has_errors(&self) -> bool {
+                self.error.is_some()
+                        || self.secondary
+                                .iter()
+                                .any(|secondary| secondary.severity == Severity::Error)
+        }
+
+        /// The output of the parser, or `None` if it failed.
+        pub fn into_output(self) -> Option<O> {
+                self.output
+        }
+
+        /// The secondary errors (including warnings and notes), followed by the fatal error, if there is one.
+        pub fn into_errors(self) -> Vec<E::Error> {
+                self.secondary
+                        .into_iter()
+                        .map(|secondary| secondary.err)
+                        .chain(self.error)
+                        .collect()
+        }
+
+        /// Splits this result into the output, the secondary errors and the fatal error.
+        pub fn into_parts(self) -> ParseParts<I, O, E> {
+                (self.output, self.secondary, self.error)
+        }
+
+        /// Turns this result into a [`PResult`], discarding the secondary errors.
+        ///
+        /// # Errors
+        /// Returns the fatal error if the parser failed.
+        pub fn into_result(self) -> PResult<I, O, E> {
+                match (self.output, self.error) {
+                        (Some(output), _) => Ok(output),
+                        (None, Some(error)) => Err(error),
+                        (None, None) => {
+                                unreachable!("a parse result has either an output or an error")
+                        }
+                }
+        }
+}
+
+impl<I: InputType, O: Debug, E: ParserExtras<I>> Debug for ParseResult<I, O, E>
+where
+        I::Span: Debug,
+        E::Error: Debug,
+{
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_struct("ParseResult")
+                        .field("output", &self.output)
+                        .field("secondary", &self.secondary)
+                        .field("error", &self.error)
+                        .finish()
+        }
+}
+
 /// Implement `LabelError<I, Filtering>` to use `filter*` with your error.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Filtering(pub Cow<'static, str>);
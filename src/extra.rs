@@ -1,10 +1,12 @@
 #![cfg(feature = "builtin-extra")]
-use crate::error::Error;
+use crate::error::{Error, Filtering, Frame, LabelError, Located, Suggestion};
 use crate::input::{InputType, Span};
 use crate::parser::ParserExtras;
+use crate::primitive::SeqLabel;
 #[cfg(feature = "builtin-text")]
-use crate::text::Char;
-use std::fmt::Debug;
+use crate::text::{Char, CharLabel};
+use alloc::borrow::Cow;
+use std::fmt::{Debug, Display};
 use std::marker::PhantomData;
 
 #[derive(Default, Clone, Copy, Debug)]
@@ -15,74 +17,190 @@ impl<I: InputType, E: Error<I>> ParserExtras<I> for Err<I, E> {
         type Context = ();
 }
 
+/// Like [`Err`], but with all of the automatic recovery in sequence combinators enabled.
+/// See [`recovery::Config`](crate::recovery::Config).
+#[cfg(feature = "error-recovery")]
+#[derive(Default, Clone, Copy, Debug)]
+pub struct Recover<I: InputType, E: Error<I> = Simple<I>>(PhantomData<I>, PhantomData<E>);
+
+#[cfg(feature = "error-recovery")]
+impl<I: InputType, E: Error<I>> ParserExtras<I> for Recover<I, E> {
+        type Error = E;
+        type Context = ();
+        const RECOVERY: crate::recovery::Config = crate::recovery::Config::ALL;
+}
+
 macro_rules! simple {
         ($bound:tt) => {
+                /// A lightweight error type, and the default one of [`Err`].
+                ///
+                /// It doesn't keep suggestions ([`Error::suggest`]) or custom messages;
+                /// use [`Rich`] for those.
                 #[derive(Debug, Clone, thiserror::Error, PartialEq, Eq)]
                 pub enum Simple<I: InputType> where I::Token: $bound {
                         #[error(
-                                "expected end of file at {}..{}, but found {found:?}",
+                                "expected end of file at {}..{}, but found {found:?}{}",
                                 .span.start(),
-                                .span.end()
+                                .span.end(),
+                                DisplayFrames(.context)
                         )]
-                        ExpectedEOF { found: I::Token, span: I::Span },
+                        ExpectedEOF {
+                                found: I::Token,
+                                span: I::Span,
+                                context: Vec<Frame<I>>,
+                        },
                         #[error(
-                                "unexpected end of file at {}..{}, expected {expected:?}",
+                                "unexpected end of file at {}..{}, expected {expected:?}{}",
                                 .span.start(),
-                                .span.end()
+                                .span.end(),
+                                DisplayFrames(.context)
                         )]
                         UnexpectedEOF {
                                 span: I::Span,
                                 expected: Option<Vec<I::Token>>,
+                                context: Vec<Frame<I>>,
                         },
                         #[error(
-                                "expected {expected:?} at {}..{}, but found {found:?}",
-                                .span.start(),
-                                .span.end()
+                                "{}{}",
+                                DisplayMismatch(DisplayExpected(.expected, &[]), Some(.found), .span),
+                                DisplayFrames(.context)
                         )]
                         ExpectedTokenFound {
                                 span: I::Span,
                                 expected: Vec<I::Token>,
                                 found: I::Token,
+                                context: Vec<Frame<I>>,
+                        },
+                        /// Failed alternatives of different kinds, merged by [`Error::merge`].
+                        #[error(
+                                "{}{}",
+                                DisplayMismatch(DisplayExpected(&[], .expected), .found.as_ref(), .span),
+                                DisplayFrames(.context)
+                        )]
+                        Expected {
+                                span: I::Span,
+                                /// Everything that was expected, including patterns that aren't single tokens,
+                                /// like `identifier`.
+                                expected: Vec<RichPattern<I::Token>>,
+                                found: Option<I::Token>,
+                                context: Vec<Frame<I>>,
                         },
                         #[cfg(feature = "builtin-text")]
                         #[error(
-                                "{error} at {}..{}, last token was {last_token:?}",
+                                "{error} at {}..{}, last token was {last_token:?}{}",
                                 .span.start(),
-                                .span.end()
+                                .span.end(),
+                                DisplayFrames(.context)
                         )]
                         Text {
                                 span: I::Span,
                                 error: crate::text::CharLabel<I::Token>,
                                 last_token: Option<I::Token>,
+                                context: Vec<Frame<I>>,
                         },
                         #[error(
-                                "{label} at {}..{}, last token was {last_token:?}",
+                                "{label} at {}..{}, last token was {last_token:?}{}",
                                 .span.start(),
-                                .span.end()
+                                .span.end(),
+                                DisplayFrames(.context)
                         )]
                         Sequence {
                                 span: I::Span,
                                 label: crate::primitive::SeqLabel<I::Token>,
                                 last_token: Option<I::Token>,
+                                context: Vec<Frame<I>>,
                         },
                         #[error(
-                                "{} at {}..{}, last token was {last_token:?}",
+                                "{} at {}..{}, last token was {last_token:?}{}",
                                 .label.0,
                                 .span.start(),
-                                .span.end()
+                                .span.end(),
+                                DisplayFrames(.context)
                         )]
                         Filtering {
                                 span: I::Span,
                                 label: crate::error::Filtering,
                                 last_token: Option<I::Token>,
+                                context: Vec<Frame<I>>,
                         },
                 }
 
+                impl<I: InputType> Simple<I> where I::Token: $bound {
+                        /// Splits this error into its span, its context and what it expected and found.
+                        fn into_expectation(self) -> (I::Span, Vec<Frame<I>>, Expectation<I::Token>) {
+                                let tokens = |tokens: Vec<I::Token>| tokens.into_iter().map(RichPattern::Token).collect();
+                                let (span, context, expected, found) = match self {
+                                        Self::ExpectedEOF {
+                                                span,
+                                                context,
+                                                found,
+                                        } => (span, context, vec![RichPattern::EndOfInput], Some(found)),
+                                        Self::UnexpectedEOF {
+                                                span,
+                                                context,
+                                                expected,
+                                        } => (span, context, tokens(expected.unwrap_or_default()), None),
+                                        Self::ExpectedTokenFound {
+                                                span,
+                                                context,
+                                                expected,
+                                                found,
+                                        } => (span, context, tokens(expected), Some(found)),
+                                        Self::Expected {
+                                                span,
+                                                context,
+                                                expected,
+                                                found,
+                                        } => (span, context, expected, found),
+                                        #[cfg(feature = "builtin-text")]
+                                        Self::Text {
+                                                span,
+                                                context,
+                                                error,
+                                                last_token,
+                                        } => (span, context, vec![RichPattern::of_char_label(error)], last_token),
+                                        Self::Sequence {
+                                                span,
+                                                context,
+                                                label,
+                                                last_token,
+                                        } => (span, context, RichPattern::of_seq_label(label), last_token),
+                                        Self::Filtering {
+                                                span,
+                                                context,
+                                                label,
+                                                last_token,
+                                        } => (span, context, vec![RichPattern::Label(label.0)], last_token),
+                                };
+                                (span, context, Expectation { expected, found })
+                        }
+
+                        /// The context frames this error was produced in, innermost first. See [`Parser::in_context`].
+                        ///
+                        /// [`Parser::in_context`]: crate::parser::Parser::in_context
+                        pub fn context(&self) -> &[Frame<I>] {
+                                match self {
+                                        Self::ExpectedEOF { context, .. }
+                                        | Self::UnexpectedEOF { context, .. }
+                                        | Self::ExpectedTokenFound { context, .. }
+                                        | Self::Expected { context, .. }
+                                        | Self::Sequence { context, .. }
+                                        | Self::Filtering { context, .. } => context,
+                                        #[cfg(feature = "builtin-text")]
+                                        Self::Text { context, .. } => context,
+                                }
+                        }
+                }
+
                 impl<I: InputType> Error<I>
                         for Simple<I> where I::Token: $bound
                 {
                         fn expected_eof_found(span: I::Span, found: I::Token) -> Self {
-                                Self::ExpectedEOF { found, span }
+                                Self::ExpectedEOF {
+                                        found,
+                                        span,
+                                        context: vec![],
+                                }
                         }
                         fn expected_token_found(
                                 span: I::Span,
@@ -93,13 +211,105 @@ macro_rules! simple {
                                         span,
                                         expected,
                                         found,
+                                        context: vec![],
                                 }
                         }
                         fn unexpected_eof(
                                 span: I::Span,
                                 expected: Option<Vec<<I as InputType>::Token>>,
                         ) -> Self {
-                                Self::UnexpectedEOF { span, expected }
+                                Self::UnexpectedEOF {
+                                        span,
+                                        expected,
+                                        context: vec![],
+                                }
+                        }
+                        fn merge(self, other: Self) -> Self {
+                                match (self, other) {
+                                        (
+                                                Self::ExpectedTokenFound {
+                                                        span,
+                                                        mut expected,
+                                                        found,
+                                                        context,
+                                                },
+                                                Self::ExpectedTokenFound {
+                                                        expected: other, ..
+                                                },
+                                        ) => {
+                                                extend_unique(&mut expected, other);
+                                                Self::ExpectedTokenFound {
+                                                        span,
+                                                        expected,
+                                                        found,
+                                                        context,
+                                                }
+                                        }
+                                        (
+                                                Self::UnexpectedEOF {
+                                                        span,
+                                                        expected,
+                                                        context,
+                                                },
+                                                Self::UnexpectedEOF {
+                                                        expected: other, ..
+                                                },
+                                        ) => Self::UnexpectedEOF {
+                                                span,
+                                                expected: match (expected, other) {
+                                                        (Some(mut expected), Some(other)) => {
+                                                                extend_unique(&mut expected, other);
+                                                                Some(expected)
+                                                        }
+                                                        (expected, other) => expected.or(other),
+                                                },
+                                                context,
+                                        },
+                                        (this @ Self::ExpectedEOF { .. }, Self::ExpectedEOF { .. }) => this,
+                                        // different kinds of errors are combined, so that nothing expected is lost
+                                        (this, other) => {
+                                                let (span, context, mut this) = this.into_expectation();
+                                                let (_, _, other) = other.into_expectation();
+                                                extend_unique(&mut this.expected, other.expected);
+                                                Self::Expected {
+                                                        span,
+                                                        expected: this.expected,
+                                                        found: this.found.or(other.found),
+                                                        context,
+                                                }
+                                        }
+                                }
+                        }
+                        fn in_context(mut self, frame: Frame<I>) -> Self {
+                                match &mut self {
+                                        Self::ExpectedEOF { context, .. }
+                                        | Self::UnexpectedEOF { context, .. }
+                                        | Self::ExpectedTokenFound { context, .. }
+                                        | Self::Expected { context, .. }
+                                        | Self::Sequence { context, .. }
+                                        | Self::Filtering { context, .. } => context.push(frame),
+                                        #[cfg(feature = "builtin-text")]
+                                        Self::Text { context, .. } => context.push(frame),
+                                }
+                                self
+                        }
+
+                        fn expected_tokens(&self) -> Vec<&I::Token> {
+                                match self {
+                                        Self::UnexpectedEOF {
+                                                expected: Some(expected),
+                                                ..
+                                        }
+                                        | Self::ExpectedTokenFound { expected, .. } => expected.iter().collect(),
+                                        Self::Expected { expected, .. } => expected
+                                                .iter()
+                                                .filter_map(|pattern| match pattern {
+                                                        RichPattern::Token(token) => Some(token),
+                                                        _ => None,
+                                                })
+                                                .collect(),
+                                        _ => vec![],
+                                }
                         }
                 }
 
@@ -115,6 +325,7 @@ macro_rules! simple {
                                         span,
                                         label,
                                         last_token,
+                                        context: vec![],
                                 }
                         }
                 }
@@ -131,10 +342,82 @@ macro_rules! simple {
                                         span,
                                         label,
                                         last_token,
+                                        context: vec![],
                                 }
                         }
                 }
 
+                impl<I: InputType> crate::report::Diagnostic for Simple<I>
+                where
+                        I::Token: $bound + Debug,
+                        I::Span: Span<Offset = usize>,
+                {
+                        type Span = I::Span;
+
+                        fn span(&self) -> &I::Span {
+                                match self {
+                                        Self::ExpectedEOF { span, .. }
+                                        | Self::UnexpectedEOF { span, .. }
+                                        | Self::ExpectedTokenFound { span, .. }
+                                        | Self::Expected { span, .. }
+                                        | Self::Sequence { span, .. }
+                                        | Self::Filtering { span, .. } => span,
+                                        #[cfg(feature = "builtin-text")]
+                                        Self::Text { span, .. } => span,
+                                }
+                        }
+
+                        fn message(&self) -> String {
+                                match self {
+                                        Self::ExpectedEOF { .. } => "expected end of input".to_owned(),
+                                        Self::UnexpectedEOF { .. } => "unexpected end of input".to_owned(),
+                                        Self::ExpectedTokenFound { found, .. } => format!("unexpected {found:?}"),
+                                        Self::Expected { found, .. } => format!("unexpected {}", DisplayFound(found.as_ref())),
+                                        #[cfg(feature = "builtin-text")]
+                                        Self::Text { error, .. } => error.to_string(),
+                                        Self::Sequence { label, .. } => label.to_string(),
+                                        Self::Filtering { label, .. } => format!("expected {}", label.0),
+                                }
+                        }
+
+                        fn expected(&self) -> Vec<String> {
+                                match self {
+                                        Self::UnexpectedEOF {
+                                                expected: Some(expected),
+                                                ..
+                                        }
+                                        | Self::ExpectedTokenFound { expected, .. } => {
+                                                expected.iter().map(|token| format!("{token:?}")).collect()
+                                        }
+                                        Self::Expected { expected, .. } => {
+                                                expected.iter().map(ToString::to_string).collect()
+                                        }
+                                        _ => vec![],
+                                }
+                        }
+
+                        fn found(&self) -> Option<String> {
+                                let found = match self {
+                                        Self::ExpectedEOF { found, .. }
+                                        | Self::ExpectedTokenFound { found, .. } => Some(found),
+                                        Self::UnexpectedEOF { .. } => None,
+                                        Self::Expected { found, .. } => found.as_ref(),
+                                        Self::Sequence { last_token, .. }
+                                        | Self::Filtering { last_token, .. } => last_token.as_ref(),
+                                        #[cfg(feature = "builtin-text")]
+                                        Self::Text { last_token, .. } => last_token.as_ref(),
+                                };
+                                Some(found.map_or_else(|| "end of input".to_owned(), |found| format!("{found:?}")))
+                        }
+
+                        fn labels(&self) -> Vec<(&I::Span, String)> {
+                                self.context()
+                                        .iter()
+                                        .map(|frame| (&frame.pos, format!("while parsing {}", frame.err)))
+                                        .collect()
+                        }
+                }
+
                 #[cfg(feature = "builtin-text")]
                 impl<I: InputType>
                         crate::error::LabelError<I, crate::text::CharLabel<I::Token>> for Simple<I> where I::Token: Char
@@ -148,12 +431,100 @@ macro_rules! simple {
                                         span,
                                         error,
                                         last_token,
+                                        context: vec![],
                                 }
                         }
                 }
         };
 }
 
+/// What a [`Simple`] error expected and found, see [`Simple::Expected`].
+struct Expectation<T> {
+        expected: Vec<RichPattern<T>>,
+        found: Option<T>,
+}
+
+/// Appends the items of `other` that aren't in `items` yet.
+fn extend_unique<T: PartialEq>(items: &mut Vec<T>, other: Vec<T>) {
+        for item in other {
+                if !items.contains(&item) {
+                        items.push(item);
+                }
+        }
+}
+
+/// Displays expected tokens and patterns as `'a', 'b' or identifier`.
+struct DisplayExpected<'a, T>(&'a [T], &'a [RichPattern<T>]);
+
+impl<T: Debug> Display for DisplayExpected<'_, T> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                let tokens = self.0.iter().map(|token| format!("{token:?}"));
+                let patterns: Vec<String> = tokens
+                        .chain(self.1.iter().map(ToString::to_string))
+                        .collect();
+                for (i, pattern) in patterns.iter().enumerate() {
+                        match i {
+                                0 => {}
+                                _ if i + 1 == patterns.len() => f.write_str(" or ")?,
+                                _ => f.write_str(", ")?,
+                        }
+                        f.write_str(pattern)?;
+                }
+                Ok(())
+        }
+}
+
+/// Displays a found token, or `end of input`.
+struct DisplayFound<'a, T>(Option<&'a T>);
+
+impl<T: Debug> Display for DisplayFound<'_, T> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                match self.0 {
+                        Some(found) => write!(f, "{found:?}"),
+                        None => f.write_str("end of input"),
+                }
+        }
+}
+
+/// Displays `expected {expected} at {span}, but found {found}`, or `unexpected {found} at {span}`
+/// if nothing in particular was expected, like when [`not`](crate::primitive::not) fails.
+struct DisplayMismatch<'a, T, S>(DisplayExpected<'a, T>, Option<&'a T>, &'a S);
+
+impl<T: Debug, S: Span> Display for DisplayMismatch<'_, T, S> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                let Self(expected, found, span) = self;
+                let found = DisplayFound(*found);
+                if expected.0.is_empty() && expected.1.is_empty() {
+                        write!(f, "unexpected {found} at {}..{}", span.start(), span.end())
+                } else {
+                        write!(
+                                f,
+                                "expected {expected} at {}..{}, but found {found}",
+                                span.start(),
+                                span.end()
+                        )
+                }
+        }
+}
+
+/// Displays context frames as `, while parsing {label} at {start}..{end}`, innermost first.
+struct DisplayFrames<'a, S>(&'a [Located<S, Cow<'static, str>>]);
+
+impl<S: Span> Display for DisplayFrames<'_, S> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                for frame in self.0 {
+                        write!(
+                                f,
+                                ", while parsing {} at {}..{}",
+                                frame.err,
+                                frame.pos.start(),
+                                frame.pos.end()
+                        )?;
+                }
+                Ok(())
+        }
+}
+
 #[cfg(feature = "builtin-text")]
 simple!(Char);
 
@@ -162,7 +533,476 @@ simple!(Nothing);
 
 #[cfg(not(feature = "builtin-text"))]
 #[doc(hidden)]
-pub trait Nothing {}
+pub trait Nothing: PartialEq + Debug {}
 
 #[cfg(not(feature = "builtin-text"))]
-impl<T: ?Sized> Nothing for T {}
+impl<T: PartialEq + Debug + ?Sized> Nothing for T {}
+
+/// Something that a [`Rich`] (or a merged [`Simple`]) error expected to find.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum RichPattern<T> {
+        /// A specific token.
+        Token(T),
+        /// A named pattern, like `identifier` or `digit with radix 10`.
+        Label(Cow<'static, str>),
+        /// The end of input.
+        EndOfInput,
+        /// Any token at all.
+        Any,
+}
+
+impl<T> RichPattern<T> {
+        /// The pattern a [`CharLabel`] expects.
+        #[cfg(feature = "builtin-text")]
+        fn of_char_label(label: CharLabel<T>) -> Self
+        where
+                T: Char,
+        {
+                Self::Label(match label {
+                        CharLabel::ExpectedIdent(_) => Cow::Borrowed("identifier"),
+                        CharLabel::ExpectedKeyword(keyword) => {
+                                Cow::Owned(format!("keyword {:?}", keyword.as_ref()))
+                        }
+                        CharLabel::ExpectedDigit(radix) => {
+                                Cow::Owned(format!("digit with radix {radix}"))
+                        }
+                        CharLabel::Newline => Cow::Borrowed("newline"),
+                        CharLabel::Whitespace => Cow::Borrowed("whitespace"),
+                        CharLabel::InlineWhitespace => Cow::Borrowed("inline whitespace"),
+                })
+        }
+
+        /// The patterns a [`SeqLabel`] expects.
+        fn of_seq_label(label: SeqLabel<T>) -> Vec<Self>
+        where
+                T: Debug,
+        {
+                match label {
+                        SeqLabel::OneOf(tokens) => tokens.into_iter().map(Self::Token).collect(),
+                        SeqLabel::NoneOf(tokens) => {
+                                vec![Self::Label(Cow::Owned(format!("anything but {tokens:?}")))]
+                        }
+                }
+        }
+}
+
+impl<T: Debug> Display for RichPattern<T> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                match self {
+                        Self::Token(token) => write!(f, "{token:?}"),
+                        Self::Label(label) => f.write_str(label),
+                        Self::EndOfInput => f.write_str("end of input"),
+                        Self::Any => f.write_str("any token"),
+                }
+        }
+}
+
+/// A rich error type, which, unlike [`Simple`], keeps everything it knows about an error in one shape:
+/// the span it occurred at, the token that was found (or `None` for the end of input),
+/// a deduplicated list of all of the [`RichPattern`]s that were expected there, and any custom messages.
+///
+/// Because of that, errors from different alternatives and different labels can be combined and inspected uniformly.
+///
+/// # Example
+/// ```
+/// # use aott::prelude::*;
+/// use aott::extra::{Rich, RichPattern};
+/// let sign = one_of::<_, extra::Err<&str, Rich<&str>>, _>("+-");
+/// let parser = sign.or(filter(|c: &char| c.is_ascii_digit(), filtering("digit")));
+///
+/// let error = parser.parse("x").unwrap_err();
+/// assert_eq!(
+///     error.expected(),
+///     &[RichPattern::Token('+'), RichPattern::Token('-'), RichPattern::Label("digit".into())]
+/// );
+/// assert_eq!(error.found(), Some(&'x'));
+/// assert_eq!(error.to_string(), "expected '+', '-' or digit at 0..1, but found 'x'");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rich<I: InputType> {
+        span: I::Span,
+        found: Option<I::Token>,
+        expected: Vec<RichPattern<I::Token>>,
+        messages: Vec<String>,
+        context: Vec<Frame<I>>,
+        suggestions: Vec<Suggestion<I::Span, I::Token>>,
+}
+
+impl<I: InputType> Rich<I> {
+        /// Creates an error with a custom message.
+        pub fn custom(span: I::Span, message: impl Into<String>) -> Self {
+                Self {
+                        span,
+                        found: None,
+                        expected: vec![],
+                        messages: vec![message.into()],
+                        context: vec![],
+                        suggestions: vec![],
+                }
+        }
+
+        /// Adds a custom message to this error.
+        #[must_use]
+        pub fn with_message(mut self, message: impl Into<String>) -> Self {
+                self.messages.push(message.into());
+                self
+        }
+
+        /// The span this error occurred at.
+        pub fn span(&self) -> &I::Span {
+                &self.span
+        }
+
+        /// The token that was found, or `None` if the end of input was found instead.
+        pub fn found(&self) -> Option<&I::Token> {
+                self.found.as_ref()
+        }
+
+        /// Everything that was expected to be found instead.
+        pub fn expected(&self) -> &[RichPattern<I::Token>] {
+                &self.expected
+        }
+
+        /// The custom messages attached to this error.
+        pub fn messages(&self) -> &[String] {
+                &self.messages
+        }
+
+        /// The context frames this error was produced in, innermost first. See [`Parser::in_context`].
+        ///
+        /// [`Parser::in_context`]: crate::parser::Parser::in_context
+        pub fn context(&self) -> &[Frame<I>] {
+                &self.context
+        }
+
+        /// The suggested fixes for this error, see [`recovery::repair`](crate::recovery::repair).
+        pub fn suggestions(&self) -> &[Suggestion<I::Span, I::Token>] {
+                &self.suggestions
+        }
+
+        fn expected_found(
+                span: I::Span,
+                expected: impl IntoIterator<Item = RichPattern<I::Token>>,
+                found: Option<I::Token>,
+        ) -> Self
+        where
+                I::Token: PartialEq,
+        {
+                let mut this = Self {
+                        span,
+                        found,
+                        expected: vec![],
+                        messages: vec![],
+                        context: vec![],
+                        suggestions: vec![],
+                };
+                this.expect(expected);
+                this
+        }
+
+        fn expect(&mut self, patterns: impl IntoIterator<Item = RichPattern<I::Token>>)
+        where
+                I::Token: PartialEq,
+        {
+                for pattern in patterns {
+                        if !self.expected.contains(&pattern) {
+                                self.expected.push(pattern);
+                        }
+                }
+        }
+}
+
+impl<I: InputType> Error<I> for Rich<I>
+where
+        I::Token: PartialEq,
+{
+        fn expected_eof_found(span: I::Span, found: I::Token) -> Self {
+                Self::expected_found(span, [RichPattern::EndOfInput], Some(found))
+        }
+
+        fn unexpected_eof(span: I::Span, expected: Option<Vec<I::Token>>) -> Self {
+                match expected {
+                        Some(expected) => Self::expected_found(
+                                span,
+                                expected.into_iter().map(RichPattern::Token),
+                                None,
+                        ),
+                        None => Self::expected_found(span, [RichPattern::Any], None),
+                }
+        }
+
+        fn expected_token_found(span: I::Span, expected: Vec<I::Token>, found: I::Token) -> Self {
+                Self::expected_found(
+                        span,
+                        expected.into_iter().map(RichPattern::Token),
+                        Some(found),
+                )
+        }
+
+        fn merge(mut self, other: Self) -> Self {
+                self.expect(other.expected);
+                self.messages.extend(other.messages);
+                self.suggestions.extend(other.suggestions);
+                self
+        }
+
+        fn in_context(mut self, frame: Frame<I>) -> Self {
+                self.context.push(frame);
+                self
+        }
+
+        fn expected_tokens(&self) -> Vec<&I::Token> {
+                self.expected
+                        .iter()
+                        .filter_map(|pattern| match pattern {
+                                RichPattern::Token(token) => Some(token),
+                                _ => None,
+                        })
+                        .collect()
+        }
+
+        fn suggest(mut self, suggestion: Suggestion<I::Span, I::Token>) -> Self {
+                self.suggestions.push(suggestion);
+                self
+        }
+}
+
+#[cfg(feature = "builtin-text")]
+impl<I: InputType> LabelError<I, CharLabel<I::Token>> for Rich<I>
+where
+        I::Token: Char,
+{
+        fn from_label(
+                span: I::Span,
+                label: CharLabel<I::Token>,
+                last_token: Option<I::Token>,
+        ) -> Self {
+                Self::expected_found(span, [RichPattern::of_char_label(label)], last_token)
+        }
+}
+
+impl<I: InputType> LabelError<I, SeqLabel<I::Token>> for Rich<I>
+where
+        I::Token: PartialEq + Debug,
+{
+        fn from_label(
+                span: I::Span,
+                label: SeqLabel<I::Token>,
+                last_token: Option<I::Token>,
+        ) -> Self {
+                Self::expected_found(span, RichPattern::of_seq_label(label), last_token)
+        }
+}
+
+impl<I: InputType> LabelError<I, Filtering> for Rich<I>
+where
+        I::Token: PartialEq,
+{
+        fn from_label(span: I::Span, label: Filtering, last_token: Option<I::Token>) -> Self {
+                Self::expected_found(span, [RichPattern::Label(label.0)], last_token)
+        }
+}
+
+impl<I: InputType> Display for Rich<I>
+where
+        I::Token: Debug,
+{
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                if !self.expected.is_empty() {
+                        f.write_str("expected ")?;
+                        for (i, pattern) in self.expected.iter().enumerate() {
+                                match i {
+                                        0 => {}
+                                        _ if i + 1 == self.expected.len() => f.write_str(" or ")?,
+                                        _ => f.write_str(", ")?,
+                                }
+                                write!(f, "{pattern}")?;
+                        }
+                        write!(
+                                f,
+                                " at {}..{}, but found ",
+                                self.span.start(),
+                                self.span.end()
+                        )?;
+                        match &self.found {
+                                Some(found) => write!(f, "{found:?}")?,
+                                None => f.write_str("end of input")?,
+                        }
+                } else if self.messages.is_empty() {
+                        // nothing in particular was expected, like when `not` fails
+                        match &self.found {
+                                Some(found) => write!(f, "unexpected {found:?}")?,
+                                None => f.write_str("unexpected end of input")?,
+                        }
+                        write!(f, " at {}..{}", self.span.start(), self.span.end())?;
+                }
+                for (i, message) in self.messages.iter().enumerate() {
+                        if i > 0 || !self.expected.is_empty() {
+                                f.write_str("; ")?;
+                        }
+                        f.write_str(message)?;
+                }
+                write!(f, "{}", DisplayFrames(&self.context))
+        }
+}
+
+impl<I: InputType> crate::report::Diagnostic for Rich<I>
+where
+        I::Token: Debug,
+        I::Span: Span<Offset = usize>,
+{
+        type Span = I::Span;
+
+        fn span(&self) -> &I::Span {
+                &self.span
+        }
+
+        fn message(&self) -> String {
+                match (&self.found, self.messages.first()) {
+                        (_, Some(message)) if self.expected.is_empty() => message.clone(),
+                        (Some(found), _) => format!("unexpected {found:?}"),
+                        (None, _) => "unexpected end of input".to_owned(),
+                }
+        }
+
+        fn expected(&self) -> Vec<String> {
+                self.expected.iter().map(ToString::to_string).collect()
+        }
+
+        fn found(&self) -> Option<String> {
+                if self.expected.is_empty() {
+                        return None;
+                }
+                Some(self
+                        .found
+                        .as_ref()
+                        .map_or_else(|| "end of input".to_owned(), |found| format!("{found:?}")))
+        }
+
+        fn notes(&self) -> Vec<String> {
+                // a custom message already used as the report's message is not repeated
+                let skip = usize::from(self.expected.is_empty());
+                self.messages.iter().skip(skip).cloned().collect()
+        }
+
+        fn help(&self) -> Vec<String> {
+                self.suggestions.iter().map(ToString::to_string).collect()
+        }
+
+        fn labels(&self) -> Vec<(&I::Span, String)> {
+                self.context
+                        .iter()
+                        .map(|frame| (&frame.pos, format!("while parsing {}", frame.err)))
+                        .collect()
+        }
+}
+
+impl<I: InputType + Debug> std::error::Error for Rich<I>
+where
+        I::Token: Debug,
+        I::Span: Debug,
+{
+}
+
+#[cfg(test)]
+mod test {
+        use crate::prelude::*;
+        use crate::report::Report;
+
+        use super::{RichPattern, Simple};
+
+        #[test]
+        fn simple_merge_keeps_labels() {
+                let parser = choice((
+                        just::<_, _, extra::Err<&str>>("if"),
+                        just("while"),
+                        text::ascii::ident,
+                ));
+                let error = parser.parse("1").unwrap_err();
+                assert_eq!(
+                        error.to_string(),
+                        "expected 'i', 'w' or identifier at 0..1, but found '1'"
+                );
+                assert_eq!(
+                        Report::new("1").render(&error),
+                        "\
+error: unexpected '1'
+ --> 1:1
+  |
+1 | 1
+  | ^
+  = expected 'i', 'w' or identifier
+  = found '1'
+"
+                );
+        }
+
+        #[test]
+        fn simple_merge_keeps_labels_in_any_order() {
+                let parser = choice((
+                        text::ascii::ident::<_, _, extra::Err<&str>>,
+                        just("if"),
+                        end.to("end"),
+                ));
+                assert_eq!(
+                        parser.parse("1"),
+                        Err(Simple::Expected {
+                                span: 0..1,
+                                expected: vec![
+                                        RichPattern::Label("identifier".into()),
+                                        RichPattern::Token('i'),
+                                        RichPattern::EndOfInput,
+                                ],
+                                found: Some('1'),
+                                context: vec![],
+                        })
+                );
+        }
+
+        #[test]
+        fn simple_merge_dedupes_tokens() {
+                let parser = choice((just::<_, _, extra::Err<&str>>("ab"), just("ac"), just("b")));
+                assert_eq!(
+                        parser.parse("x"),
+                        Err(Simple::ExpectedTokenFound {
+                                span: 0..1,
+                                expected: vec!['a', 'b'],
+                                found: 'x',
+                                context: vec![],
+                        })
+                );
+
+                let parser = just::<_, _, extra::Err<&str>>('a')
+                        .or(just('a'))
+                        .or(just('b'));
+                assert_eq!(
+                        parser.parse(""),
+                        Err(Simple::UnexpectedEOF {
+                                span: 0..0,
+                                expected: Some(vec!['a', 'b']),
+                                context: vec![],
+                        })
+                );
+        }
+
+        #[test]
+        fn simple_unexpected_token() {
+                let minus = just::<_, _, extra::Err<&str>>('-').then_ignore(not(just('>')));
+                let error = minus.parse("->").unwrap_err();
+                assert_eq!(error.to_string(), "unexpected '>' at 1..2");
+                assert_eq!(
+                        Report::new("->").render(&error),
+                        "\
+error: unexpected '>'
+ --> 1:2
+  |
+1 | ->
+  |  ^
+  = found '>'
+"
+                );
+
+                let error = just::<_, _, extra::Err<&str>>('-').parse("+").unwrap_err();
+                assert_eq!(error.to_string(), "expected '-' at 0..1, but found '+'");
+        }
+}
@@ -3,16 +3,20 @@
 #[cfg(feature = "builtin-text")]
 use crate::text::Char;
 use crate::{
-        error::{Error, Located},
+        error::{Error, Located, Secondary, Severity},
         parser::{Parser, ParserExtras},
+        primitive::MemoTables,
 };
 use std::{
         fmt::Display,
         hash::Hash,
         marker::PhantomData,
-        ops::{Range, RangeFrom},
+        ops::{Deref, DerefMut, Range, RangeFrom},
 };
 
+mod line_index;
+pub use line_index::*;
+
 pub trait Span {
         type Offset: Clone + Display;
         type Context: Clone;
@@ -80,6 +84,68 @@ impl<O: Clone + Display, C: Clone> Span for (C, Range<O>) {
         }
 }
 
+/// A value together with the span it was parsed from, like an AST node and its location in the source.
+///
+/// Usually created with [`Parser::spanned`] (`parser.spanned().map(Spanned::from)`)
+/// or [`MapExtra::spanned`](crate::primitive::MapExtra::spanned).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Spanned<T, S = Range<usize>> {
+        pub value: T,
+        pub span: S,
+}
+
+impl<T, S: Span> Spanned<T, S> {
+        pub fn new(value: T, span: S) -> Self {
+                Self { value, span }
+        }
+
+        pub fn start(&self) -> S::Offset {
+                self.span.start()
+        }
+
+        pub fn end(&self) -> S::Offset {
+                self.span.end()
+        }
+
+        /// Transforms the value, keeping its span.
+        pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Spanned<U, S> {
+                Spanned {
+                        value: f(self.value),
+                        span: self.span,
+                }
+        }
+
+        pub fn as_ref(&self) -> Spanned<&T, S>
+        where
+                S: Clone,
+        {
+                Spanned {
+                        value: &self.value,
+                        span: self.span.clone(),
+                }
+        }
+}
+
+impl<T, S> From<(T, S)> for Spanned<T, S> {
+        fn from((value, span): (T, S)) -> Self {
+                Self { value, span }
+        }
+}
+
+impl<T, S> Deref for Spanned<T, S> {
+        type Target = T;
+
+        fn deref(&self) -> &T {
+                &self.value
+        }
+}
+
+impl<T, S> DerefMut for Spanned<T, S> {
+        fn deref_mut(&mut self) -> &mut T {
+                &mut self.value
+        }
+}
+
 #[allow(clippy::module_name_repetitions)]
 pub trait InputType {
         /// The token type that this input returns.
@@ -108,8 +174,8 @@ pub trait InputType {
         #[doc(hidden)]
         unsafe fn next(&self, offset: Self::Offset) -> (Self::Offset, Option<Self::Token>);
 
-        /// Gives the offset before the given `offset`.
-        /// Usually this uses `saturating_sub` on the concrete offset type (for example `usize`).
+        /// Gives the offset of the token before the one at `offset`, or `offset` itself at the start of the input.
+        /// Like the offsets returned by `next`, it must be at a token boundary, like a char boundary for `&str`.
         #[doc(hidden)]
         fn prev(&self, offset: Self::Offset) -> Self::Offset;
 
@@ -156,6 +222,10 @@ impl<T, S: Span + Clone, I: InputType<Token = (T, S)>> InputType for SpannedInpu
                 let start = unsafe { self.input.next(range.start) }
                         .1
                         .map_or(self.eoi.start(), |tok| tok.1.start());
+                // the token before an empty range could be anywhere before it, or not exist at all
+                if range.start == range.end {
+                        return S::new(self.eoi.context(), start.clone()..start);
+                }
                 let end = unsafe { self.input.next(self.input.prev(range.end)) }
                         .1
                         .map_or(self.eoi.start(), |tok| tok.1.end());
@@ -183,7 +253,11 @@ impl<'a> InputType for &'a str {
         }
 
         fn prev(&self, offset: usize) -> usize {
-                offset.saturating_sub(1)
+                // step back over the whole character, not just its last byte
+                self[..offset]
+                        .chars()
+                        .next_back()
+                        .map_or(offset, |c| offset - c.len_utf8())
         }
 
         #[inline(always)]
@@ -240,19 +314,36 @@ pub trait ExactSizeInput: InputType {
 }
 
 #[derive(Debug, Clone)]
-pub struct Errors<L, E> {
-        pub secondary: Vec<Located<L, E>>,
+pub struct Errors<L, S, E> {
+        /// The error of the alternative that got the furthest, see [`Input::add_alt`].
+        pub alt: Option<Located<L, E>>,
+        pub secondary: Vec<Secondary<S, E>>,
+        /// How many times recovery was attempted, see [`Config::max_attempts`](crate::recovery::Config::max_attempts).
+        pub(crate) attempts: usize,
 }
 
-impl<L, E> Errors<L, E> {
-        pub fn emit(&mut self, pos: L, err: E) {
-                self.secondary.push(Located { pos, err })
+impl<L, S, E> Errors<L, S, E> {
+        /// Emit a secondary error of [`Severity::Error`] at `span`.
+        pub fn emit(&mut self, span: S, err: E) {
+                self.emit_with_severity(span, Severity::Error, err);
+        }
+
+        pub fn emit_with_severity(&mut self, span: S, severity: Severity, err: E) {
+                self.secondary.push(Secondary {
+                        span,
+                        severity,
+                        err,
+                });
         }
 }
 
-impl<L, E> Default for Errors<L, E> {
+impl<L, S, E> Default for Errors<L, S, E> {
         fn default() -> Self {
-                Self { secondary: vec![] }
+                Self {
+                        alt: None,
+                        secondary: vec![],
+                        attempts: 0,
+                }
         }
 }
 
@@ -282,6 +373,10 @@ impl<I: InputType, E: ParserExtras<I>> InputOwned<I, E> {
                         input: &self.input,
                         cx: &self.cx,
                         errors: Errors::default(),
+                        last_read: self.input.start(),
+                        #[cfg(feature = "error-recovery")]
+                        edit: None,
+                        memos: MemoTables::default(),
                 }
         }
 
@@ -291,6 +386,10 @@ impl<I: InputType, E: ParserExtras<I>> InputOwned<I, E> {
                         input: &self.input,
                         cx: &self.cx,
                         errors: Errors::default(),
+                        last_read: offset,
+                        #[cfg(feature = "error-recovery")]
+                        edit: None,
+                        memos: MemoTables::default(),
                 }
         }
 }
@@ -307,9 +406,41 @@ pub struct Input<'parse, I: InputType, E: ParserExtras<I>> {
         #[doc(hidden)]
         pub input: &'parse I,
         #[doc(hidden)]
-        pub errors: Errors<I::Offset, E::Error>,
+        pub errors: Errors<I::Offset, I::Span, E::Error>,
         #[doc(hidden)]
         pub cx: &'parse E::Context,
+        /// The offset of the last token that was read (or that was attempted to be read).
+        /// If a parser fails, this is the offset it failed at.
+        pub(crate) last_read: I::Offset,
+        /// The edit of the input that [`repair`](crate::recovery::repair) is trying out.
+        #[cfg(feature = "error-recovery")]
+        pub(crate) edit: Option<Edit<I>>,
+        /// The results of [memoized](crate::parser::Parser::memoized) parsers during this parse.
+        pub(crate) memos: MemoTables,
+}
+
+/// A single-token edit of the input, which reading tokens pretends was made. See [`Input::edit`].
+#[cfg(feature = "error-recovery")]
+pub(crate) struct Edit<I: InputType> {
+        /// Where the edit is made.
+        pub(crate) at: I::Offset,
+        /// The token inserted at `at`, or `None` if the token at `at` is deleted.
+        pub(crate) insert: Option<I::Token>,
+        /// Clones the inserted token, which is read again whenever the input is rewound before the edit.
+        pub(crate) clone: fn(&I::Token) -> I::Token,
+        /// Whether the edit has been read past.
+        pub(crate) applied: bool,
+}
+
+#[cfg(feature = "error-recovery")]
+impl<I: InputType> std::fmt::Debug for Edit<I> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_struct("Edit")
+                        .field("at", &self.at.into())
+                        .field("delete", &self.insert.is_none())
+                        .field("applied", &self.applied)
+                        .finish_non_exhaustive()
+        }
 }
 
 impl<'parse, I: InputType, E: ParserExtras<I, Context = ()>> Input<'parse, I, E> {
@@ -319,6 +450,10 @@ impl<'parse, I: InputType, E: ParserExtras<I, Context = ()>> Input<'parse, I, E>
                         input,
                         errors: Errors::default(),
                         cx: &(),
+                        last_read: input.start(),
+                        #[cfg(feature = "error-recovery")]
+                        edit: None,
+                        memos: MemoTables::default(),
                 }
         }
 }
@@ -333,6 +468,10 @@ impl<'parse, I: InputType, E: ParserExtras<I>> Input<'parse, I, E> {
                         input,
                         errors: Errors::default(),
                         cx,
+                        last_read: input.start(),
+                        #[cfg(feature = "error-recovery")]
+                        edit: None,
+                        memos: MemoTables::default(),
                 }
         }
 
@@ -344,6 +483,7 @@ impl<'parse, I: InputType, E: ParserExtras<I>> Input<'parse, I, E> {
         #[inline(always)]
         pub(crate) fn skip_while(&mut self, f: &impl Fn(&I::Token) -> bool) {
                 loop {
+                        self.last_read = self.offset;
                         // SAFETY: offset was generated by previous call to `Input::next`
                         let (offset, token) = unsafe { self.input.next(self.offset) };
                         if token.filter(f).is_none() {
@@ -355,10 +495,17 @@ impl<'parse, I: InputType, E: ParserExtras<I>> Input<'parse, I, E> {
 
         #[inline(always)]
         pub(crate) fn next_inner(&mut self) -> (I::Offset, Option<I::Token>) {
+                self.last_read = self.offset;
+                #[cfg(feature = "error-recovery")]
+                if let Some(token) = self.take_insertion() {
+                        return (self.offset, Some(token));
+                }
                 // SAFETY: offset was generated by previous call to `Input::next`
                 let (offset, token) = unsafe { self.input.next(self.offset) };
                 self.offset = offset;
-                (offset, token)
+                #[cfg(feature = "error-recovery")]
+                self.skip_deletion();
+                (self.offset, token)
         }
 
         /// Invokes `parser` with this input.
@@ -379,6 +526,8 @@ impl<'parse, I: InputType, E: ParserExtras<I>> Input<'parse, I, E> {
                 Marker {
                         offset: self.offset,
                         err_count: self.errors.secondary.len(),
+                        #[cfg(feature = "error-recovery")]
+                        edit_applied: self.edit.as_ref().is_some_and(|edit| edit.applied),
                 }
         }
 
@@ -389,6 +538,95 @@ impl<'parse, I: InputType, E: ParserExtras<I>> Input<'parse, I, E> {
         pub fn rewind(&mut self, marker: Marker<I>) {
                 self.errors.secondary.truncate(marker.err_count);
                 self.offset = marker.offset;
+                #[cfg(feature = "error-recovery")]
+                if let Some(edit) = &mut self.edit {
+                        edit.applied = marker.edit_applied;
+                }
+        }
+
+        /// The edit being tried out, if it is at the current offset and hasn't been read past yet.
+        #[cfg(feature = "error-recovery")]
+        fn pending_edit(&mut self) -> Option<&mut Edit<I>> {
+                let offset = self.offset;
+                self.edit
+                        .as_mut()
+                        .filter(|edit| !edit.applied && edit.at == offset)
+        }
+
+        /// Read the token inserted at the current offset, if there is one.
+        #[cfg(feature = "error-recovery")]
+        fn take_insertion(&mut self) -> Option<I::Token> {
+                let edit = self.pending_edit()?;
+                let token = edit.insert.as_ref().map(edit.clone)?;
+                edit.applied = true;
+                Some(token)
+        }
+
+        /// Skip the token deleted at the current offset, if there is one.
+        ///
+        /// Deletions are applied as soon as they are reached, rather than when the next token is read,
+        /// so that parsers starting right after a deleted token don't include it in their spans.
+        #[cfg(feature = "error-recovery")]
+        pub(crate) fn skip_deletion(&mut self) {
+                let Some(edit) = self.pending_edit() else {
+                        return;
+                };
+                if edit.insert.is_none() {
+                        edit.applied = true;
+                        // SAFETY: offset was generated by previous call to `Input::next`
+                        self.offset = unsafe { self.input.next(self.offset) }.0;
+                }
+        }
+
+        /// Record the error of an alternative that has just failed.
+        ///
+        /// Of all the recorded errors, the one that failed the furthest into the input is kept.
+        /// Errors of alternatives that failed at the same offset are combined with [`Error::merge`].
+        /// Use [`Self::take_alt`] to get the resulting error once all of the alternatives have failed.
+        pub fn add_alt(&mut self, error: E::Error) {
+                let pos = self.last_read;
+                self.errors.alt = Some(match self.errors.alt.take() {
+                        Some(alt) if alt.pos > pos => alt,
+                        Some(alt) if alt.pos == pos => Located::at(pos, alt.err.merge(error)),
+                        _ => Located::at(pos, error),
+                });
+        }
+
+        /// Take the error recorded by [`Self::add_alt`].
+        ///
+        /// # Panics
+        /// Panics if no alternative has failed yet.
+        pub fn take_alt(&mut self) -> E::Error {
+                let alt = self
+                        .errors
+                        .alt
+                        .take()
+                        .expect("no alternative has failed yet");
+                self.last_read = alt.pos;
+                alt.err
+        }
+
+        /// Whether recovery from an error may be attempted, within the limits of [`ParserExtras::RECOVERY`].
+        /// If so, the attempt is counted.
+        #[cfg(feature = "error-recovery")]
+        pub(crate) fn attempt_recovery(&mut self) -> bool {
+                let config = E::RECOVERY;
+                let errors = || {
+                        self.errors
+                                .secondary
+                                .iter()
+                                .filter(|secondary| secondary.severity == Severity::Error)
+                                .count()
+                };
+                if config
+                        .max_attempts
+                        .is_some_and(|max| self.errors.attempts >= max)
+                        || config.max_errors.is_some_and(|max| errors() >= max)
+                {
+                        return false;
+                }
+                self.errors.attempts += 1;
+                true
         }
 
         #[inline(always)]
@@ -406,6 +644,14 @@ impl<'parse, I: InputType, E: ParserExtras<I>> Input<'parse, I, E> {
         #[inline(always)]
         pub fn peek(&self) -> Result<I::Token, E::Error> {
                 let befunge = self.offset;
+                #[cfg(feature = "error-recovery")]
+                if let Some(edit) = &self.edit {
+                        if !edit.applied && edit.at == self.offset {
+                                if let Some(token) = &edit.insert {
+                                        return Ok((edit.clone)(token));
+                                }
+                        }
+                }
                 // SAFETY: offset was generated by previous call to `Input::next`
                 unsafe { self.input.next(self.offset).1 }
                         .ok_or_else(|| Error::unexpected_eof(self.span_since(befunge), None))
@@ -422,7 +668,12 @@ impl<'parse, I: InputType, E: ParserExtras<I>> Input<'parse, I, E> {
         }
         #[inline(always)]
         pub fn skip(&mut self) -> Result<(), E::Error> {
+                #[cfg(feature = "error-recovery")]
+                if self.edit.is_some() {
+                        return self.next().map(drop);
+                }
                 let before = self.offset;
+                self.last_read = before;
                 self.offset = Some(unsafe { self.input.next(self.offset) })
                         .and_then(|x| x.1.map(|_| x.0))
                         .ok_or_else(|| Error::unexpected_eof(self.span_since(before), None))?;
@@ -468,6 +719,10 @@ impl<'parse, I: InputType, E: ParserExtras<I>> Input<'parse, I, E> {
                         cx,
                         errors: Errors::default(),
                         offset: self.offset,
+                        last_read: self.last_read,
+                        #[cfg(feature = "error-recovery")]
+                        edit: None,
+                        memos: MemoTables::default(),
                 }
         }
 
@@ -478,6 +733,10 @@ impl<'parse, I: InputType, E: ParserExtras<I>> Input<'parse, I, E> {
                         cx: &(),
                         errors: Errors::default(),
                         offset: self.offset,
+                        last_read: self.last_read,
+                        #[cfg(feature = "error-recovery")]
+                        edit: None,
+                        memos: MemoTables::default(),
                 }
         }
 
@@ -517,14 +776,13 @@ impl<'parse, I: InputType, E: ParserExtras<I>> Input<'parse, I, E> {
 pub struct Marker<I: InputType> {
         pub offset: I::Offset,
         err_count: usize,
+        #[cfg(feature = "error-recovery")]
+        edit_applied: bool,
 }
 
 impl<I: InputType> Clone for Marker<I> {
         fn clone(&self) -> Self {
-                Self {
-                        offset: self.offset,
-                        err_count: self.err_count,
-                }
+                *self
         }
 }
 
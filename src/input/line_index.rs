@@ -0,0 +1,252 @@
+use super::Span;
+use std::ops::Range;
+
+/// Characters that end a line on their own, apart from `\n` and `\r\n`. See [`text::newline`](crate::text::newline).
+pub(crate) static NEWLINE_CHARACTERS_AFTER_CRLF: [char; 6] = [
+        '\r',       // Carriage return
+        '\x0B',     // Vertical tab
+        '\x0C',     // Form feed
+        '\u{0085}', // Next line
+        '\u{2028}', // Line separator
+        '\u{2029}', // Paragraph separator
+];
+
+/// The unit columns are counted in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ColumnUnit {
+        /// UTF-8 bytes, the unit spans of `&str` use.
+        Byte,
+        /// Unicode scalar values ([`char`]s).
+        Char,
+        /// UTF-16 code units, the unit most editors and LSP clients use.
+        Utf16,
+}
+
+impl ColumnUnit {
+        fn len(self, c: char) -> usize {
+                match self {
+                        Self::Byte => c.len_utf8(),
+                        Self::Char => 1,
+                        Self::Utf16 => c.len_utf16(),
+                }
+        }
+}
+
+/// A zero-based line and column position in some source.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LineCol {
+        pub line: usize,
+        pub col: usize,
+}
+
+/// Maps byte offsets in a source to line and column positions, and back again.
+///
+/// Lines are terminated by every line terminator [`text::newline`](crate::text::newline) recognises:
+/// `\n`, `\r\n`, `\r`, vertical tab, form feed, `\u{0085}`, `\u{2028}` and `\u{2029}`.
+///
+/// # Example
+/// ```
+/// use aott::input::{ColumnUnit, LineCol, LineIndex};
+/// let index = LineIndex::new("let x = 1;\r\nlet 𝑦 = x;");
+///
+/// assert_eq!(index.line_col(21, ColumnUnit::Byte), Some(LineCol { line: 1, col: 9 }));
+/// assert_eq!(index.line_col(21, ColumnUnit::Char), Some(LineCol { line: 1, col: 6 }));
+/// assert_eq!(index.line_col(21, ColumnUnit::Utf16), Some(LineCol { line: 1, col: 7 }));
+/// assert_eq!(index.offset(LineCol { line: 1, col: 7 }, ColumnUnit::Utf16), Some(21));
+///
+/// // converting spans works the same way
+/// let position = index.line_cols(&(16..20), ColumnUnit::Utf16);
+/// assert_eq!(position, Some(LineCol { line: 1, col: 4 }..LineCol { line: 1, col: 6 }));
+/// assert_eq!(index.offsets(position.unwrap(), ColumnUnit::Utf16), Some(16..20));
+/// ```
+#[derive(Clone, Debug)]
+pub struct LineIndex<'a> {
+        source: &'a str,
+        line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+        /// Indexes the lines of `source`.
+        #[must_use]
+        pub fn new(source: &'a str) -> Self {
+                let mut line_starts = vec![0];
+                let mut chars = source.char_indices().peekable();
+                while let Some((i, c)) = chars.next() {
+                        let is_newline = match c {
+                                '\n' => true,
+                                // the `\n` of a `\r\n` ends the line
+                                '\r' => !matches!(chars.peek(), Some((_, '\n'))),
+                                c => NEWLINE_CHARACTERS_AFTER_CRLF.contains(&c),
+                        };
+                        if is_newline {
+                                line_starts.push(i + c.len_utf8());
+                        }
+                }
+                Self {
+                        source,
+                        line_starts,
+                }
+        }
+
+        /// The source this index was built from.
+        #[must_use]
+        pub fn source(&self) -> &'a str {
+                self.source
+        }
+
+        /// The number of lines in the source. An empty source, or one ending with a line terminator,
+        /// has an empty last line.
+        #[must_use]
+        pub fn line_count(&self) -> usize {
+                self.line_starts.len()
+        }
+
+        /// The text of `line`, without its line terminator.
+        #[must_use]
+        pub fn line(&self, line: usize) -> Option<&'a str> {
+                let start = *self.line_starts.get(line)?;
+                let end = self
+                        .line_starts
+                        .get(line + 1)
+                        .copied()
+                        .unwrap_or(self.source.len());
+                let text = &self.source[start..end];
+                Some(text.strip_suffix("\r\n").unwrap_or_else(|| {
+                        text.strip_suffix(|c| {
+                                c == '\n' || NEWLINE_CHARACTERS_AFTER_CRLF.contains(&c)
+                        })
+                        .unwrap_or(text)
+                }))
+        }
+
+        /// The line and column of the byte `offset`, with the column counted in `unit`s.
+        ///
+        /// Returns `None` if `offset` is past the end of the source or is not on a char boundary.
+        /// An offset inside of a line terminator is on the line that the terminator ends.
+        #[must_use]
+        pub fn line_col(&self, offset: usize, unit: ColumnUnit) -> Option<LineCol> {
+                if !self.source.is_char_boundary(offset) {
+                        return None;
+                }
+                let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+                let col = self.source[self.line_starts[line]..offset]
+                        .chars()
+                        .map(|c| unit.len(c))
+                        .sum();
+                Some(LineCol { line, col })
+        }
+
+        /// The byte offset of `position`, with its column counted in `unit`s.
+        ///
+        /// Returns `None` if the line does not exist, the column is past the end of the line,
+        /// or the column is in the middle of a character.
+        #[must_use]
+        pub fn offset(&self, position: LineCol, unit: ColumnUnit) -> Option<usize> {
+                let start = *self.line_starts.get(position.line)?;
+                let text = self.line(position.line)?;
+                let mut col = 0;
+                for (i, c) in text.char_indices() {
+                        if col >= position.col {
+                                return (col == position.col).then_some(start + i);
+                        }
+                        col += unit.len(c);
+                }
+                (col == position.col).then_some(start + text.len())
+        }
+
+        /// The line and column positions of the start and end of `span`. See [`LineIndex::line_col`].
+        #[must_use]
+        pub fn line_cols<S: Span<Offset = usize>>(
+                &self,
+                span: &S,
+                unit: ColumnUnit,
+        ) -> Option<Range<LineCol>> {
+                Some(self.line_col(span.start(), unit)?..self.line_col(span.end(), unit)?)
+        }
+
+        /// The byte offsets of the start and end of `range`. See [`LineIndex::offset`].
+        #[must_use]
+        pub fn offsets(&self, range: Range<LineCol>, unit: ColumnUnit) -> Option<Range<usize>> {
+                Some(self.offset(range.start, unit)?..self.offset(range.end, unit)?)
+        }
+}
+
+#[cfg(test)]
+mod test {
+        use super::*;
+
+        #[test]
+        fn every_newline_ends_a_line() {
+                let index = LineIndex::new("a\nb\r\nc\rd\x0Be\x0Cf\u{85}g\u{2028}h\u{2029}i");
+                assert_eq!(index.line_count(), 9);
+                for (line, text) in ["a", "b", "c", "d", "e", "f", "g", "h", "i"]
+                        .iter()
+                        .enumerate()
+                {
+                        assert_eq!(index.line(line), Some(*text));
+                }
+                assert_eq!(index.line(9), None);
+        }
+
+        #[test]
+        fn crlf_is_a_single_terminator() {
+                let index = LineIndex::new("a\r\n\r\nb");
+                assert_eq!(index.line_count(), 3);
+                assert_eq!(index.line(1), Some(""));
+                assert_eq!(
+                        index.line_col(2, ColumnUnit::Byte),
+                        Some(LineCol { line: 0, col: 2 })
+                );
+                assert_eq!(
+                        index.line_col(5, ColumnUnit::Byte),
+                        Some(LineCol { line: 2, col: 0 })
+                );
+        }
+
+        #[test]
+        fn round_trips_every_offset() {
+                let source = "fn 𝑓(x) {\u{2028}\tλ ü\r\n}\n";
+                let index = LineIndex::new(source);
+                for unit in [ColumnUnit::Byte, ColumnUnit::Char, ColumnUnit::Utf16] {
+                        for line in 0..index.line_count() {
+                                let start = index.line_starts[line];
+                                let text = index.line(line).unwrap();
+                                // offsets inside of line terminators don't map back, so only check the text
+                                for offset in (start..=start + text.len())
+                                        .filter(|&i| source.is_char_boundary(i))
+                                {
+                                        let position = index.line_col(offset, unit).unwrap();
+                                        assert_eq!(position.line, line);
+                                        assert_eq!(
+                                                index.offset(position, unit),
+                                                Some(offset),
+                                                "{unit:?}"
+                                        );
+                                }
+                        }
+                }
+        }
+
+        #[test]
+        fn rejects_invalid_positions() {
+                let index = LineIndex::new("𝑓\nx");
+                assert_eq!(index.line_col(1, ColumnUnit::Byte), None);
+                assert_eq!(index.line_col(7, ColumnUnit::Byte), None);
+                assert_eq!(
+                        index.offset(LineCol { line: 0, col: 1 }, ColumnUnit::Utf16),
+                        None
+                );
+                assert_eq!(
+                        index.offset(LineCol { line: 0, col: 2 }, ColumnUnit::Utf16),
+                        Some(4)
+                );
+                assert_eq!(
+                        index.offset(LineCol { line: 0, col: 3 }, ColumnUnit::Utf16),
+                        None
+                );
+                assert_eq!(
+                        index.offset(LineCol { line: 2, col: 0 }, ColumnUnit::Utf16),
+                        None
+                );
+        }
+}
@@ -40,6 +40,166 @@ pub trait IterParser<I: InputType, E: ParserExtras<I>> {
         fn until<UO, U: Parser<I, UO, E>>(self, until: U) -> Until<Self, U, UO> where Self: Sized {
             Until(self, until, PhantomData)
         }
+
+        /// Creates a parser that folds all of the items yielded by this parser into `init` with `f`,
+        /// without collecting them first.
+        ///
+        /// # Example
+        /// ```
+        /// # use aott::prelude::*;
+        /// let balance = one_of::<_, extra::Err<&str>, _>("+-")
+        ///     .repeated()
+        ///     .fold(0, |balance, sign| if sign == '+' { balance + 1 } else { balance - 1 });
+        ///
+        /// assert_eq!(balance.parse("++-+"), Ok(2));
+        /// ```
+        fn fold<U: Clone, F: Fn(U, Self::Item) -> U>(self, init: U, f: F) -> Fold<Self, U, F>
+        where
+                Self: Sized,
+        {
+                Fold(self, init, f)
+        }
+
+        /// Creates a parser that parses `seed` first, and then folds the items yielded by this parser
+        /// into its output from the left. Useful for left-associative chains like `a.b.c`.
+        ///
+        /// # Example
+        /// ```
+        /// # use aott::prelude::*;
+        /// let object = text::ascii::ident::<_, _, extra::Err<&str>>.map(str::to_owned);
+        /// let access = just('.')
+        ///     .ignore_then(text::ascii::ident)
+        ///     .repeated()
+        ///     .foldl(object, |object, field| format!("({object}.{field})"));
+        ///
+        /// assert_eq!(access.parse("a.b.c"), Ok("((a.b).c)".to_owned()));
+        /// ```
+        fn foldl<OA, A: Parser<I, OA, E>, F: Fn(OA, Self::Item) -> OA>(
+                self,
+                seed: A,
+                f: F,
+        ) -> FoldL<Self, A, OA, F>
+        where
+                Self: Sized,
+        {
+                FoldL(self, seed, f, PhantomData)
+        }
+
+        /// Creates a parser that parses the items of this parser first, then `seed`, and folds the items
+        /// into the output of `seed` from the right. Useful for right-associative chains like prefix operators.
+        ///
+        /// # Example
+        /// ```
+        /// # use aott::prelude::*;
+        /// let operand = text::ascii::ident::<_, _, extra::Err<&str>>.map(str::to_owned);
+        /// let unary = one_of("-!")
+        ///     .repeated()
+        ///     .foldr(operand, |op, operand| format!("({op}{operand})"));
+        ///
+        /// assert_eq!(unary.parse("-!x"), Ok("(-(!x))".to_owned()));
+        /// ```
+        fn foldr<OA, A: Parser<I, OA, E>, F: Fn(Self::Item, OA) -> OA>(
+                self,
+                seed: A,
+                f: F,
+        ) -> FoldR<Self, A, OA, F>
+        where
+                Self: Sized,
+        {
+                FoldR(self, seed, f, PhantomData)
+        }
+}
+
+/// See [`IterParser::fold`].
+pub struct Fold<P, U, F>(P, U, F);
+
+impl<
+                I: InputType,
+                E: ParserExtras<I>,
+                P: IterParser<I, E>,
+                U: Clone,
+                F: Fn(U, P::Item) -> U,
+        > Parser<I, U, E> for Fold<P, U, F>
+{
+        fn parse_with(&self, input: &mut Input<I, E>) -> crate::PResult<I, U, E> {
+                let mut state = self.0.create_state(input)?;
+                let mut acc = self.1.clone();
+                while let Some(item) = self.0.next(input, &mut state)? {
+                        acc = (self.2)(acc, item);
+                }
+                Ok(acc)
+        }
+
+        fn check_with(&self, input: &mut Input<I, E>) -> crate::PResult<I, (), E> {
+                check_all(&self.0, input)
+        }
+}
+
+/// See [`IterParser::foldl`].
+pub struct FoldL<P, A, OA, F>(P, A, F, PhantomData<OA>);
+
+impl<
+                I: InputType,
+                E: ParserExtras<I>,
+                P: IterParser<I, E>,
+                OA,
+                A: Parser<I, OA, E>,
+                F: Fn(OA, P::Item) -> OA,
+        > Parser<I, OA, E> for FoldL<P, A, OA, F>
+{
+        fn parse_with(&self, input: &mut Input<I, E>) -> crate::PResult<I, OA, E> {
+                let mut acc = self.1.parse_with(input)?;
+                let mut state = self.0.create_state(input)?;
+                while let Some(item) = self.0.next(input, &mut state)? {
+                        acc = (self.2)(acc, item);
+                }
+                Ok(acc)
+        }
+
+        fn check_with(&self, input: &mut Input<I, E>) -> crate::PResult<I, (), E> {
+                self.1.check_with(input)?;
+                check_all(&self.0, input)
+        }
+}
+
+/// See [`IterParser::foldr`].
+pub struct FoldR<P, A, OA, F>(P, A, F, PhantomData<OA>);
+
+impl<
+                I: InputType,
+                E: ParserExtras<I>,
+                P: IterParser<I, E>,
+                OA,
+                A: Parser<I, OA, E>,
+                F: Fn(P::Item, OA) -> OA,
+        > Parser<I, OA, E> for FoldR<P, A, OA, F>
+{
+        fn parse_with(&self, input: &mut Input<I, E>) -> crate::PResult<I, OA, E> {
+                let mut state = self.0.create_state(input)?;
+                let mut items = vec![];
+                while let Some(item) = self.0.next(input, &mut state)? {
+                        items.push(item);
+                }
+                let seed = self.1.parse_with(input)?;
+                Ok(items.into_iter()
+                        .rev()
+                        .fold(seed, |acc, item| (self.2)(item, acc)))
+        }
+
+        fn check_with(&self, input: &mut Input<I, E>) -> crate::PResult<I, (), E> {
+                check_all(&self.0, input)?;
+                self.1.check_with(input)
+        }
+}
+
+/// Checks all of the items of `parser`.
+fn check_all<I: InputType, E: ParserExtras<I>, P: IterParser<I, E>>(
+        parser: &P,
+        input: &mut Input<I, E>,
+) -> crate::PResult<I, (), E> {
+        let mut state = parser.create_state(input)?;
+        while parser.check_next(input, &mut state)?.is_some() {}
+        Ok(())
 }
 
 pub struct Collect<P, B>(P, PhantomData<B>);
@@ -165,3 +325,35 @@ impl<I: InputType, E: ParserExtras<I>, P: IterParser<I, E>, U: Parser<I, UO, E>,
                 self.0.check_next(input, state)
         }
 }
+
+#[cfg(test)]
+mod test {
+        use crate::prelude::*;
+
+        fn sign<'a>() -> impl IterParser<&'a str, extra::Err<&'a str>, Item = char> {
+                one_of("+-").repeated()
+        }
+
+        #[test]
+        fn folds_in_check_mode() {
+                let fold = sign().fold(0, |_, _: char| -> i32 { unreachable!() });
+                assert_eq!(fold.slice().parse("+-+"), Ok("+-+"));
+
+                let foldl = sign().foldl(text::int(10), |_, _| unreachable!());
+                assert_eq!(foldl.slice().parse_prefix("12+-x"), Ok(("12+-", "x")));
+
+                let foldr = sign().foldr(text::int(10), |_, _| unreachable!());
+                assert_eq!(foldr.slice().parse("-+12"), Ok("-+12"));
+        }
+
+        #[test]
+        fn folds_fail_with_their_seed() {
+                let foldl = sign().foldl(text::int(10), |number: &str, _| number);
+                assert!(foldl.parse("+1").is_err());
+                assert!(foldl.slice().parse("+1").is_err());
+
+                let foldr = sign().foldr(text::int(10), |_, number: &str| number);
+                assert!(foldr.parse("+-").is_err());
+                assert!(foldr.slice().parse("+-").is_err());
+        }
+}
@@ -31,9 +31,11 @@ pub mod extra;
 pub mod input;
 pub mod iter;
 pub mod parser;
+pub mod pratt;
 pub mod primitive;
 #[cfg(feature = "error-recovery")]
 pub mod recovery;
+pub mod report;
 pub mod stream;
 pub use aott_derive as derive;
 #[cfg(feature = "serialization")]
@@ -56,7 +58,10 @@ pub mod prelude {
         pub use crate::parser::{Parser, ParserExtras};
         pub use crate::primitive::*;
         #[cfg(feature = "error-recovery")]
-        pub use crate::recovery::{noop, via_parser, Strategy};
+        pub use crate::recovery::{
+                nested_delimiters, noop, repair, skip_then_retry_until, skip_until, via_parser,
+                Strategy,
+        };
         pub use crate::stream::Stream;
         #[cfg(feature = "builtin-text")]
         pub use crate::text;
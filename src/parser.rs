@@ -1,8 +1,9 @@
+use alloc::borrow::Cow;
 use std::marker::PhantomData;
 
 use crate::{
-        error::Error,
-        input::{Input, InputType, SliceInput},
+        error::{Error, ParseResult, Secondary, Severity},
+        input::{Input, InputType, SliceInput, Span},
         primitive::*,
         sync::RefC,
         *,
@@ -205,6 +206,115 @@ pub trait Parser<I: InputType, O, E: ParserExtras<I>> {
                 self.parse_with(&mut input)
         }
 
+        /// Invokes this parser on the specified input, requiring all of it to be consumed.
+        ///
+        /// If the parser succeeds without consuming the whole input, an "expected end of input" error is returned,
+        /// spanning all of the unconsumed remainder and reporting the first unconsumed token as found.
+        ///
+        /// # Errors
+        /// Returns an error if the parser failed, or did not consume the whole input.
+        ///
+        /// # Example
+        /// ```
+        /// # use aott::prelude::*;
+        /// use aott::extra::Simple;
+        /// let number = text::int::<_, _, extra::Err<&str>>(10);
+        /// assert_eq!(number.parse_all("42"), Ok("42"));
+        /// assert_eq!(
+        ///     number.parse_all("42abc"),
+        ///     Err(Simple::ExpectedEOF { found: 'a', span: 2..5, context: vec![] })
+        /// );
+        /// ```
+        #[track_caller]
+        fn parse_all(&self, input: I) -> PResult<I, O, E>
+        where
+                E: ParserExtras<I, Context = ()>,
+        {
+                let mut input = Input::new(&input);
+                let output = self.parse_with(&mut input)?;
+
+                let before = input.offset;
+                match input.next_or_none() {
+                        None => Ok(output),
+                        Some(found) => {
+                                while input.next_or_none().is_some() {}
+                                Err(Error::expected_eof_found(input.span_since(before), found))
+                        }
+                }
+        }
+
+        /// Invokes this parser on the specified input, returning its output together with the unconsumed rest
+        /// of the input, so that parsing can be continued later.
+        ///
+        /// # Errors
+        /// Returns an error if the parser failed.
+        ///
+        /// # Example
+        /// ```
+        /// # use aott::prelude::*;
+        /// let number = text::int::<_, _, extra::Err<&str>>(10);
+        /// assert_eq!(number.parse_prefix("42, 43"), Ok(("42", ", 43")));
+        /// assert_eq!(number.parse_prefix("42"), Ok(("42", "")));
+        /// ```
+        #[track_caller]
+        fn parse_prefix<'a>(&self, input: I) -> PResult<I, (O, I::Slice), E>
+        where
+                I: SliceInput<'a>,
+                E: ParserExtras<I, Context = ()>,
+        {
+                let mut input = Input::new(&input);
+                let output = self.parse_with(&mut input)?;
+                Ok((output, input.input.slice_from(input.offset..)))
+        }
+
+        /// Invokes this parser on the specified input, keeping the secondary errors it recovered from.
+        ///
+        /// Unlike [`Parser::parse`], which only returns the output or the fatal error,
+        /// this also returns the errors emitted by [recovery strategies](crate::recovery) and [`Parser::validate`].
+        ///
+        /// # Example
+        /// ```
+        /// # use aott::prelude::*;
+        /// use aott::extra::Rich;
+        /// let byte = text::int::<_, _, extra::Err<&str, Rich<&str>>>(10)
+        ///     .validate(|number: &str, extra, emitter| {
+        ///         number.parse::<u8>().unwrap_or_else(|_| {
+        ///             emitter.emit(Rich::custom(extra.span(), "number does not fit into a byte"));
+        ///             u8::MAX
+        ///         })
+        ///     });
+        ///
+        /// let result = byte.parse_recovering("256");
+        /// assert_eq!(result.output(), Some(&255));
+        /// assert_eq!(result.secondary()[0].err.messages(), ["number does not fit into a byte"]);
+        ///
+        /// let result = byte.parse_recovering("x");
+        /// assert_eq!(result.output(), None);
+        /// assert!(result.error().is_some());
+        /// ```
+        #[track_caller]
+        fn parse_recovering(&self, input: I) -> ParseResult<I, O, E>
+        where
+                E: ParserExtras<I, Context = ()>,
+        {
+                let mut input = Input::new(&input);
+                let result = self.parse_with(&mut input);
+                ParseResult::new(result, input.errors.secondary)
+        }
+
+        /// Invokes this parser on this input with a context, keeping the secondary errors it recovered from.
+        /// See [`Parser::parse_recovering`].
+        #[track_caller]
+        fn parse_recovering_with_context(
+                &self,
+                input: I,
+                context: E::Context,
+        ) -> ParseResult<I, O, E> {
+                let mut input = Input::new_with_context(&input, &context);
+                let result = self.parse_with(&mut input);
+                ParseResult::new(result, input.errors.secondary)
+        }
+
         /// Runs the parser logic, producing an output, or an error.
         /// # Errors
         /// Returns an error if the parser failed.
@@ -231,6 +341,27 @@ pub trait Parser<I: InputType, O, E: ParserExtras<I>> {
 
         /// Transform this parser to try and invoke the `other` parser on failure, and if that one fails, fail too.
         /// If you are chaining a lot of [`or`](`Parser::or`) calls, please consider using [`choice`].
+        ///
+        /// If both parsers fail, the error of the one that got the furthest into the input is returned.
+        /// If they failed at the same place, their errors are combined with [`Error::merge`].
+        ///
+        /// # Example
+        /// ```
+        /// # use aott::prelude::*;
+        /// use aott::extra::Simple;
+        /// let parser = just::<_, _, extra::Err<&str>>("if").or(just("while"));
+        ///
+        /// // both alternatives failed at the first character, so what they expected is merged
+        /// assert_eq!(
+        ///     parser.parse("for"),
+        ///     Err(Simple::ExpectedTokenFound { span: 0..1, expected: vec!['i', 'w'], found: 'f', context: vec![] })
+        /// );
+        /// // "while" got further than "if", so its error wins
+        /// assert_eq!(
+        ///     parser.parse("whale"),
+        ///     Err(Simple::ExpectedTokenFound { span: 2..3, expected: vec!['i'], found: 'a', context: vec![] })
+        /// );
+        /// ```
         fn or<P: Parser<I, O, E>>(self, other: P) -> Or<Self, P>
         where
                 Self: Sized,
@@ -288,6 +419,130 @@ pub trait Parser<I: InputType, O, E: ParserExtras<I>> {
                 TryMapWith(self, f, PhantomData, PhantomData)
         }
 
+        /// Transform the output of this parser with access to its span, the parsed slice and the
+        /// context via [`MapExtra`].
+        ///
+        /// # Example
+        /// ```
+        /// # use aott::prelude::*;
+        /// use aott::input::Spanned;
+        /// let ident = text::ascii::ident::<_, _, extra::Err<&str>>
+        ///     .map_with(|name, extra| extra.spanned(name.to_owned()));
+        /// let idents = ident.separated_by(just(' ')).collect::<Vec<_>>();
+        ///
+        /// assert_eq!(
+        ///     idents.parse("let x"),
+        ///     Ok(vec![Spanned::new("let".to_owned(), 0..3), Spanned::new("x".to_owned(), 4..5)])
+        /// );
+        /// ```
+        fn map_with<U, F>(self, f: F) -> MapWith<Self, F, O>
+        where
+                Self: Sized,
+                F: for<'input, 'parse> Fn(O, &mut MapExtra<'input, 'parse, I, E>) -> U,
+        {
+                MapWith(self, f, EmptyPhantom::new())
+        }
+
+        /// Pair the output of this parser with its span.
+        ///
+        /// Use `.spanned().map(Spanned::from)` to get a [`Spanned`](crate::input::Spanned) instead.
+        ///
+        /// # Example
+        /// ```
+        /// # use aott::prelude::*;
+        /// let number = text::int::<_, _, extra::Err<&str>>(10).padded().spanned();
+        ///
+        /// assert_eq!(number.parse(" 42 "), Ok(("42", 0..4)));
+        /// ```
+        fn spanned(self) -> WithSpan<Self, O>
+        where
+                Self: Sized,
+        {
+                WithSpan(self, EmptyPhantom::new())
+        }
+
+        /// Discard the output of this parser and output its span instead.
+        ///
+        /// # Example
+        /// ```
+        /// # use aott::prelude::*;
+        /// let comment = just::<_, _, extra::Err<&str>>("//").then(any.repeated()).to_span();
+        ///
+        /// assert_eq!(comment.parse("// hi"), Ok(0..5));
+        /// ```
+        fn to_span(self) -> ToSpan<Self, O>
+        where
+                Self: Sized,
+        {
+                ToSpan(self, EmptyPhantom::new())
+        }
+
+        /// Transform the error this parser fails with.
+        ///
+        /// # Example
+        /// ```
+        /// # use aott::prelude::*;
+        /// use aott::extra::Rich;
+        /// let port = text::digits::<_, _, extra::Err<&str, Rich<&str>>>(10)
+        ///     .slice()
+        ///     .map_err(|error| Rich::custom(error.span().clone(), "port must be numeric"));
+        ///
+        /// assert_eq!(port.parse("http").unwrap_err().to_string(), "port must be numeric");
+        /// ```
+        fn map_err<F: Fn(E::Error) -> E::Error>(self, f: F) -> MapErr<Self, F>
+        where
+                Self: Sized,
+        {
+                MapErr(self, f)
+        }
+
+        /// Transform the error this parser fails with, with access to the span of the failed attempt
+        /// (from where this parser started up to where it failed) and other [`MapExtra`] data.
+        ///
+        /// # Example
+        /// ```
+        /// # use aott::prelude::*;
+        /// use aott::extra::Rich;
+        /// let port = (just::<_, _, extra::Err<&str, Rich<&str>>>("port "), text::digits(10).slice())
+        ///     .map_err_with_span(|_, extra| Rich::custom(extra.span(), "port must be numeric"));
+        ///
+        /// let error = port.parse("port http").unwrap_err();
+        /// assert_eq!(error.span(), &(0..5));
+        /// ```
+        fn map_err_with_span<F>(self, f: F) -> MapErrWithSpan<Self, F>
+        where
+                Self: Sized,
+                F: for<'input, 'parse> Fn(
+                        E::Error,
+                        &mut MapExtra<'input, 'parse, I, E>,
+                ) -> E::Error,
+        {
+                MapErrWithSpan(self, f)
+        }
+
+        /// If this parser fails, rewind the input and call `f` with the error,
+        /// which can recover by returning a fallback output, or fail with a (possibly different) error.
+        ///
+        /// # Example
+        /// ```
+        /// # use aott::prelude::*;
+        /// use aott::extra::Simple;
+        /// let port = text::int::<_, _, extra::Err<&str>>(10).or_else(|error| match error {
+        ///     Simple::UnexpectedEOF { .. } => Ok("80"),
+        ///     error => Err(error),
+        /// });
+        ///
+        /// assert_eq!(port.parse("8080"), Ok("8080"));
+        /// assert_eq!(port.parse(""), Ok("80"));
+        /// assert!(port.parse("http").is_err());
+        /// ```
+        fn or_else<F: Fn(E::Error) -> PResult<I, O, E>>(self, f: F) -> OrElse<Self, F>
+        where
+                Self: Sized,
+        {
+                OrElse(self, f)
+        }
+
         fn filter<F: Fn(&O) -> bool, L: Clone, LF: Fn(O) -> L>(
                 self,
                 f: F,
@@ -299,6 +554,75 @@ pub trait Parser<I: InputType, O, E: ParserExtras<I>> {
                 FilterParser(self, f, label, PhantomData)
         }
 
+        /// Label this parser, so that when it fails without consuming any input,
+        /// its error is replaced with one saying that `label` was expected.
+        ///
+        /// This is useful for naming grammar rules: errors coming from deep inside of, for example, an `expression`
+        /// would otherwise leak low-level expectations, like a digit or an opening parenthesis.
+        /// If the parser fails after consuming input, the original error is kept, as it is more specific.
+        ///
+        /// The label can be anything your error implements [`LabelError`](crate::error::LabelError) for.
+        /// To label a `#[parser]` function, use `#[parser(label)]` (labelled with the function name) or `#[parser(label = "...")]`.
+        ///
+        /// # Example
+        /// ```
+        /// # use aott::prelude::*;
+        /// use aott::extra::Simple;
+        /// let number = text::digits::<_, _, extra::Err<&str>>(10).slice().labelled(filtering("number"));
+        /// assert_eq!(
+        ///     number.parse("x"),
+        ///     Err(Simple::Filtering { span: 0..1, label: filtering("number"), last_token: Some('x'), context: vec![] })
+        /// );
+        ///
+        /// let call = (just::<_, _, extra::Err<&str>>('('), just(')')).labelled(filtering("call"));
+        /// // the parser consumed the `(` before failing, so the more specific error is kept
+        /// assert_eq!(
+        ///     call.parse("(x"),
+        ///     Err(Simple::ExpectedTokenFound { span: 1..2, expected: vec![')'], found: 'x', context: vec![] })
+        /// );
+        /// ```
+        fn labelled<L: Clone>(self, label: L) -> Labelled<Self, L>
+        where
+                Self: Sized,
+        {
+                Labelled {
+                        parser: self,
+                        label,
+                }
+        }
+
+        /// Run this parser in a named context, so that if it fails, a context frame is added to its error.
+        ///
+        /// The frame holds `label` and the span from where this parser started up to where it failed,
+        /// and is passed to [`Error::in_context`](crate::error::Error::in_context) as the error propagates outwards.
+        /// When contexts are nested, the error receives the innermost frame first.
+        ///
+        /// # Example
+        /// ```
+        /// # use aott::prelude::*;
+        /// use aott::extra::Rich;
+        /// let args = (just::<_, _, extra::Err<&str, Rich<&str>>>('('), just(')')).in_context("argument list");
+        /// let call = (just("foo"), args).in_context("call of function `foo`");
+        ///
+        /// let error = call.parse("foo(x").unwrap_err();
+        /// let context: Vec<_> = error.context().iter().map(|frame| (frame.err.as_ref(), frame.pos.clone())).collect();
+        /// assert_eq!(context, [("argument list", 3..4), ("call of function `foo`", 0..4)]);
+        /// assert_eq!(
+        ///     error.to_string(),
+        ///     "expected ')' at 4..5, but found 'x', while parsing argument list at 3..4, \
+        ///      while parsing call of function `foo` at 0..4"
+        /// );
+        /// ```
+        fn in_context(self, label: impl Into<Cow<'static, str>>) -> InContext<Self>
+        where
+                Self: Sized,
+        {
+                InContext {
+                        parser: self,
+                        label: label.into(),
+                }
+        }
+
         /// # Example
         /// ```
         /// # use aott::prelude::*;
@@ -315,6 +639,7 @@ pub trait Parser<I: InputType, O, E: ParserExtras<I>> {
                         parser: self,
                         at_least: 0,
                         at_most: !0,
+                        on_error: Stop,
                         phantom: PhantomData,
                         #[cfg(debug_assertions)]
                         location: std::panic::Location::caller().clone(),
@@ -332,6 +657,7 @@ pub trait Parser<I: InputType, O, E: ParserExtras<I>> {
                         at_most: !0,
                         delimiter,
                         parser: self,
+                        on_error: Stop,
                         phantom: PhantomData,
                 }
         }
@@ -370,6 +696,26 @@ pub trait Parser<I: InputType, O, E: ParserExtras<I>> {
                 Maybe(self)
         }
 
+        /// Parse with this parser, but only if `other` also matches at the same position.
+        /// The input `other` matched is not consumed, and its output is discarded.
+        ///
+        /// # Example
+        /// ```
+        /// # use aott::prelude::*;
+        /// // an identifier that isn't a keyword
+        /// let keyword = choice((just("let"), just("fn"))).then_ignore(not(text::ascii::ident));
+        /// let ident = text::ascii::ident::<_, _, extra::Err<&str>>.and_is(not(keyword));
+        ///
+        /// assert_eq!(ident.parse("letter"), Ok("letter"));
+        /// assert!(ident.parse("let").is_err());
+        /// ```
+        fn and_is<OB, P: Parser<I, OB, E>>(self, other: P) -> AndIs<Self, P, OB>
+        where
+                Self: Sized,
+        {
+                AndIs(self, other, EmptyPhantom::new())
+        }
+
         fn delimited_by<P: Parser<I, O1, E>, T: Parser<I, O2, E>, O1, O2>(
                 self,
                 preceding: P,
@@ -381,13 +727,38 @@ pub trait Parser<I: InputType, O, E: ParserExtras<I>> {
                 Delimited(preceding, self, terminating, PhantomData)
         }
 
+        /// Validate the output of this parser, emitting secondary errors, warnings or notes through the [`Emitter`]
+        /// without making the parser fail.
+        ///
+        /// They are located at the span of this parser's output, unless emitted with an explicit span.
+        /// Use [`Parser::parse_recovering`] to get them.
+        ///
+        /// # Example
+        /// ```
+        /// # use aott::prelude::*;
+        /// use aott::{error::Severity, extra::Rich};
+        /// let label = (text::ascii::ident::<_, _, extra::Err<&str, Rich<&str>>>, just(':'))
+        ///     .validate(|(name, _): (&str, char), extra, emitter| {
+        ///         if name.starts_with('_') {
+        ///             let span = extra.span();
+        ///             emitter.warn_at(span.start..span.end - 1, Rich::custom(span.clone(), "unused label"));
+        ///         }
+        ///         name
+        ///     });
+        ///
+        /// let result = label.parse_recovering("_retry:");
+        /// assert_eq!(result.output(), Some(&"_retry"));
+        /// assert!(!result.has_errors());
+        /// assert_eq!(result.secondary()[0].span, 0..6);
+        /// assert_eq!(result.secondary()[0].severity, Severity::Warning);
+        /// ```
         fn validate<U, F>(self, f: F) -> Validate<Self, F, O>
         where
                 Self: Sized,
                 F: for<'input, 'parse> Fn(
                         O,
                         &mut MapExtra<'input, 'parse, I, E>,
-                        &mut Emitter<E::Error>,
+                        &mut Emitter<I::Span, E::Error>,
                 ) -> U,
         {
                 Validate {
@@ -397,6 +768,60 @@ pub trait Parser<I: InputType, O, E: ParserExtras<I>> {
                 }
         }
 
+        /// Parse an expression of operands parsed by this parser, combined with the prefix, postfix and infix
+        /// `operators` by their binding power and associativity.
+        ///
+        /// See the [`pratt`] module for details and an example.
+        fn pratt<T: crate::pratt::Operator<I, O, E>>(
+                self,
+                operators: T,
+        ) -> crate::pratt::Pratt<Self, T>
+        where
+                Self: Sized,
+        {
+                crate::pratt::Pratt {
+                        atom: self,
+                        operators,
+                }
+        }
+
+        /// Cache the results of this parser, so that parsing again at an offset it was already tried at
+        /// (like when [`choice`] backtracks into another alternative starting with it) takes the cached result
+        /// instead of running it again. This makes grammars that would backtrack exponentially run in linear time.
+        ///
+        /// A result is cached with where the parser stopped and the secondary errors it emitted, which are emitted again
+        /// every time it is reused. The cache is kept by the input, so it's shared by the clones of the parser,
+        /// and only lasts for a single parse.
+        ///
+        /// # Example
+        /// ```
+        /// # use aott::prelude::*;
+        /// use std::sync::atomic::{AtomicUsize, Ordering};
+        /// static RUNS: AtomicUsize = AtomicUsize::new(0);
+        /// fn number<'a>(input: &mut Input<&'a str, extra::Err<&'a str>>) -> PResult<&'a str, u32, extra::Err<&'a str>> {
+        ///     RUNS.fetch_add(1, Ordering::Relaxed);
+        ///     text::int(10).parse_with(input).map(|n: &str| n.parse().unwrap())
+        /// }
+        ///
+        /// let number = number.memoized();
+        /// let expr = choice((
+        ///     (number.clone(), just('+'), number.clone()).map(|(a, _, b)| a + b),
+        ///     (number.clone(), just('-'), number.clone()).map(|(a, _, b)| a - b),
+        ///     number,
+        /// ));
+        ///
+        /// assert_eq!(expr.parse("7-3"), Ok(4));
+        /// // both alternatives parsed the `7`, but only the first one ran `number` to do that
+        /// assert_eq!(RUNS.load(Ordering::Relaxed), 2);
+        /// ```
+        fn memoized(self) -> Memoized<Self>
+        where
+                Self: Sized,
+                O: Clone,
+        {
+                Memoized::new(self)
+        }
+
         #[cfg(feature = "builtin-text")]
         fn padded(self) -> crate::text::Padded<Self, I::Token>
         where
@@ -433,6 +858,12 @@ impl<I: InputType, O, E: ParserExtras<I>, F: Fn(&mut Input<I, E>) -> PResult<I,
 pub trait ParserExtras<I: InputType> {
         type Error: Error<I>;
         type Context;
+
+        /// Which sequence combinators automatically resynchronise when one of their elements fails,
+        /// and how much recovery is allowed.
+        /// Defaults to [`Config::NONE`](crate::recovery::Config::NONE), see [`recovery::Config`](crate::recovery::Config).
+        #[cfg(feature = "error-recovery")]
+        const RECOVERY: crate::recovery::Config = crate::recovery::Config::NONE;
 }
 
 /// See [`Parser::boxed`].
@@ -541,7 +972,7 @@ impl<
                 F: for<'input, 'parse> Fn(
                         O,
                         &mut MapExtra<'input, 'parse, I, E>,
-                        &mut Emitter<E::Error>,
+                        &mut Emitter<I::Span, E::Error>,
                 ) -> U,
         > Parser<I, U, E> for Validate<A, F, O>
 {
@@ -552,19 +983,21 @@ impl<
                 let before = input.offset;
                 let out = self.parser.parse_with(input)?;
 
-                let mut emitter = Emitter(vec![]);
+                let mut emitter = Emitter {
+                        span: input.span_since(before),
+                        emitted: vec![],
+                };
                 let checked = (self.validator)(
                         out,
                         &mut MapExtra {
                                 start: before,
+                                end: None,
                                 input,
                         },
                         &mut emitter,
                 );
 
-                for error in emitter.0 {
-                        input.errors.emit(input.offset, error);
-                }
+                input.errors.secondary.extend(emitter.emitted);
 
                 Ok(M::bind(|| checked))
         }
@@ -572,9 +1005,54 @@ impl<
         go_extra!(U);
 }
 
-pub struct Emitter<E>(pub(crate) Vec<E>);
-impl<E> Emitter<E> {
+/// Emits secondary errors, warnings and notes from [`Parser::validate`].
+///
+/// Unless a span is given explicitly, they are located at the span of the validated parser's output.
+pub struct Emitter<S, E> {
+        span: S,
+        emitted: Vec<Secondary<S, E>>,
+}
+
+impl<S: Span, E> Emitter<S, E> {
+        /// Emit an error at the span of the validated parser's output.
         pub fn emit(&mut self, error: E) {
-                self.0.push(error);
+                self.emit_at(self.span(), error);
+        }
+
+        /// Emit an error at `span`.
+        pub fn emit_at(&mut self, span: S, error: E) {
+                self.push(span, Severity::Error, error);
+        }
+
+        /// Emit a warning at the span of the validated parser's output.
+        pub fn warn(&mut self, warning: E) {
+                self.warn_at(self.span(), warning);
+        }
+
+        /// Emit a warning at `span`.
+        pub fn warn_at(&mut self, span: S, warning: E) {
+                self.push(span, Severity::Warning, warning);
+        }
+
+        /// Emit a note at the span of the validated parser's output.
+        pub fn note(&mut self, note: E) {
+                self.note_at(self.span(), note);
+        }
+
+        /// Emit a note at `span`.
+        pub fn note_at(&mut self, span: S, note: E) {
+                self.push(span, Severity::Note, note);
+        }
+
+        fn span(&self) -> S {
+                S::new(self.span.context(), self.span.range())
+        }
+
+        fn push(&mut self, span: S, severity: Severity, err: E) {
+                self.emitted.push(Secondary {
+                        span,
+                        severity,
+                        err,
+                });
         }
 }
@@ -0,0 +1,625 @@
+//! Operator-precedence parsing, also known as Pratt parsing.
+//!
+//! Instead of hand-rolling one [`recursive`](crate::primitive::recursive) rule per precedence level,
+//! use [`Parser::pratt`] with a parser for the operands (the "atoms") and a tuple of operators:
+//! [`infix`], [`prefix`] and [`postfix`] ones. Every operator has a binding power -
+//! the higher it is, the tighter the operator binds its operands - and infix operators also have an [`Associativity`].
+//!
+//! Operators are combined with their operands by folding functions,
+//! which get a [`MapExtra`] to access the span of the whole operation.
+//! When several operators could apply at the same place, the first one in the tuple wins.
+//!
+//! # Example
+//! ```
+//! # use aott::prelude::*;
+//! use aott::pratt::{infix, left, postfix, prefix, right};
+//! #[derive(Debug, PartialEq)]
+//! enum Expr {
+//!     Num(u32),
+//!     Neg(Box<Expr>),
+//!     Fact(Box<Expr>),
+//!     Bin(Box<Expr>, char, Box<Expr>),
+//! }
+//!
+//! let atom = text::int::<_, _, extra::Err<&str>>(10).map(|n: &str| Expr::Num(n.parse().unwrap()));
+//! let expr = atom.pratt((
+//!     infix(left(1), just('+').or(just('-')), |lhs, op, rhs, _| Expr::Bin(Box::new(lhs), op, Box::new(rhs))),
+//!     infix(left(2), just('*').or(just('/')), |lhs, op, rhs, _| Expr::Bin(Box::new(lhs), op, Box::new(rhs))),
+//!     infix(right(3), just('^'), |lhs, op, rhs, _| Expr::Bin(Box::new(lhs), op, Box::new(rhs))),
+//!     prefix(4, just('-'), |_, rhs, _| Expr::Neg(Box::new(rhs))),
+//!     postfix(5, just('!'), |lhs, _, _| Expr::Fact(Box::new(lhs))),
+//! ));
+//!
+//! use Expr::*;
+//! let bin = |lhs, op, rhs| Bin(Box::new(lhs), op, Box::new(rhs));
+//! assert_eq!(
+//!     expr.parse("1-2*-3!"),
+//!     Ok(bin(Num(1), '-', bin(Num(2), '*', Neg(Box::new(Fact(Box::new(Num(3))))))))
+//! );
+//! assert_eq!(expr.parse("2^3^4"), Ok(bin(Num(2), '^', bin(Num(3), '^', Num(4)))));
+//! assert_eq!(expr.parse("1-2-3"), Ok(bin(bin(Num(1), '-', Num(2)), '-', Num(3))));
+//! ```
+use crate::{
+        go_extra,
+        input::{Input, InputType},
+        parser::{Mode, Parser, ParserExtras},
+        primitive::MapExtra,
+        EmptyPhantom, PResult,
+};
+
+/// How an [`infix`] operator groups with other operators of the same binding power.
+///
+/// The binding power is stored with the associativity; see [`left`] and [`right`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Associativity {
+        /// `a - b - c` is `(a - b) - c`.
+        Left(u16),
+        /// `a ^ b ^ c` is `a ^ (b ^ c)`.
+        Right(u16),
+}
+
+/// A left-associative infix operator with the binding power `power`.
+#[must_use]
+pub const fn left(power: u16) -> Associativity {
+        Associativity::Left(power)
+}
+
+/// A right-associative infix operator with the binding power `power`.
+#[must_use]
+pub const fn right(power: u16) -> Associativity {
+        Associativity::Right(power)
+}
+
+impl Associativity {
+        /// The binding power of this operator.
+        #[must_use]
+        pub const fn power(self) -> u16 {
+                match self {
+                        Self::Left(power) | Self::Right(power) => power,
+                }
+        }
+
+        /// The minimum binding power of operators in the right-hand operand.
+        fn rhs_power(self) -> u32 {
+                match self {
+                        // an operator of the same power can't be in the right-hand operand, so it's folded into the left one
+                        Self::Left(power) => u32::from(power) + 1,
+                        Self::Right(power) => u32::from(power),
+                }
+        }
+}
+
+/// The parser that operands of an operator are parsed with: the whole expression,
+/// but only with operators of at least the given binding power.
+type Operand<'a, I, O, E, M> =
+        dyn Fn(&mut Input<I, E>, u32) -> PResult<I, <M as Mode>::Output<O>, E> + 'a;
+
+/// An expression folded with an operator, or the left-hand operand given back as `Err`
+/// if there was no operator to fold it with.
+type Folded<M, O> = Result<<M as Mode>::Output<O>, <M as Mode>::Output<O>>;
+
+/// An operator, or a tuple of operators, that [`Parser::pratt`] can combine operands with.
+///
+/// See [`infix`], [`prefix`] and [`postfix`].
+pub trait Operator<I: InputType, O, E: ParserExtras<I>> {
+        /// Parse a prefix operator and its operand, rewinding the input if that fails.
+        #[doc(hidden)]
+        fn parse_prefix<M: Mode>(
+                &self,
+                input: &mut Input<I, E>,
+                operand: &Operand<'_, I, O, E, M>,
+        ) -> Option<M::Output<O>>;
+
+        /// Parse a postfix or infix operator (and its right-hand operand) of at least `min_power`
+        /// and fold it with `lhs`, which started at `start`.
+        /// If there is none, the input is rewound and `lhs` is given back as `Ok(Err(lhs))`.
+        /// If the right-hand operand fails after the operator, so does the whole expression.
+        #[doc(hidden)]
+        fn parse_postfix_or_infix<M: Mode>(
+                &self,
+                input: &mut Input<I, E>,
+                start: I::Offset,
+                lhs: M::Output<O>,
+                min_power: u32,
+                operand: &Operand<'_, I, O, E, M>,
+        ) -> PResult<I, Folded<M, O>, E>;
+}
+
+/// See [`infix`].
+pub struct Infix<A, F, Op, I, O, E> {
+        pub(crate) op: A,
+        pub(crate) associativity: Associativity,
+        pub(crate) fold: F,
+        pub(crate) phantom: EmptyPhantom<(Op, I, O, E)>,
+}
+
+impl<A: Clone, F: Clone, Op, I, O, E> Clone for Infix<A, F, Op, I, O, E> {
+        fn clone(&self) -> Self {
+                Self {
+                        op: self.op.clone(),
+                        associativity: self.associativity,
+                        fold: self.fold.clone(),
+                        phantom: self.phantom,
+                }
+        }
+}
+
+/// An infix operator, parsed with `op` between two operands.
+/// The operands and the output of `op` are combined by `fold`, in the order they appear in.
+///
+/// Once `op` is parsed, the right-hand operand has to follow it: if it doesn't, the whole expression fails
+/// with the operand's error.
+///
+/// # Example
+/// ```
+/// # use aott::prelude::*;
+/// use aott::{extra::Simple, pratt::{infix, left}, text::CharLabel};
+/// let atom = text::int::<_, _, extra::Err<&str>>(10).map(|n: &str| n.parse::<i64>().unwrap());
+/// let expr = atom.pratt((
+///     infix(left(1), just('-'), |lhs, _, rhs, _| lhs - rhs),
+///     infix(left(2), just('/'), |lhs, _, rhs, _| lhs / rhs),
+/// ));
+///
+/// assert_eq!(expr.parse("100-8/2-1"), Ok(95));
+/// // once the operator is parsed, the right-hand operand has to follow it
+/// assert_eq!(
+///     expr.parse_prefix("1-x"),
+///     Err(Simple::Text {
+///         span: 2..3,
+///         error: CharLabel::ExpectedDigit(10),
+///         last_token: Some('x'),
+///         context: vec![],
+///     })
+/// );
+/// ```
+pub fn infix<I, O, E, A, F, Op>(
+        associativity: Associativity,
+        op: A,
+        fold: F,
+) -> Infix<A, F, Op, I, O, E>
+where
+        I: InputType,
+        E: ParserExtras<I>,
+        A: Parser<I, Op, E>,
+        F: for<'input, 'parse> Fn(O, Op, O, &mut MapExtra<'input, 'parse, I, E>) -> O,
+{
+        Infix {
+                op,
+                associativity,
+                fold,
+                phantom: EmptyPhantom::new(),
+        }
+}
+
+impl<I, O, E, A, F, Op> Operator<I, O, E> for Infix<A, F, Op, I, O, E>
+where
+        I: InputType,
+        E: ParserExtras<I>,
+        A: Parser<I, Op, E>,
+        F: for<'input, 'parse> Fn(O, Op, O, &mut MapExtra<'input, 'parse, I, E>) -> O,
+{
+        fn parse_prefix<M: Mode>(
+                &self,
+                _: &mut Input<I, E>,
+                _: &Operand<'_, I, O, E, M>,
+        ) -> Option<M::Output<O>> {
+                None
+        }
+
+        fn parse_postfix_or_infix<M: Mode>(
+                &self,
+                input: &mut Input<I, E>,
+                start: I::Offset,
+                lhs: M::Output<O>,
+                min_power: u32,
+                operand: &Operand<'_, I, O, E, M>,
+        ) -> PResult<I, Folded<M, O>, E> {
+                if u32::from(self.associativity.power()) < min_power {
+                        return Ok(Err(lhs));
+                }
+                let before = input.save();
+                let Ok(op) = self.op.go::<M>(input) else {
+                        input.rewind(before);
+                        return Ok(Err(lhs));
+                };
+                let rhs = operand(input, self.associativity.rhs_power())?;
+                Ok(Ok(M::combine(
+                        M::combine(lhs, op, |lhs, op| (lhs, op)),
+                        rhs,
+                        |(lhs, op), rhs| {
+                                (self.fold)(
+                                        lhs,
+                                        op,
+                                        rhs,
+                                        &mut MapExtra {
+                                                start,
+                                                end: None,
+                                                input,
+                                        },
+                                )
+                        },
+                )))
+        }
+}
+
+/// See [`prefix`].
+pub struct Prefix<A, F, Op, I, O, E> {
+        pub(crate) op: A,
+        pub(crate) power: u16,
+        pub(crate) fold: F,
+        pub(crate) phantom: EmptyPhantom<(Op, I, O, E)>,
+}
+
+impl<A: Clone, F: Clone, Op, I, O, E> Clone for Prefix<A, F, Op, I, O, E> {
+        fn clone(&self) -> Self {
+                Self {
+                        op: self.op.clone(),
+                        power: self.power,
+                        fold: self.fold.clone(),
+                        phantom: self.phantom,
+                }
+        }
+}
+
+/// A prefix operator with the binding power `power`, parsed with `op` before its operand.
+/// The output of `op` and the operand are combined by `fold`.
+///
+/// If the operand can't be parsed after `op`, the input is rewound and the other operators,
+/// then the atom parser, are tried instead - so a prefix `-` can coexist with negative number literals.
+///
+/// # Example
+/// ```
+/// # use aott::prelude::*;
+/// use aott::pratt::{infix, left, prefix};
+/// let atom = text::int::<_, _, extra::Err<&str>>(10).map(|n: &str| n.parse::<i64>().unwrap());
+/// let expr = atom.pratt((
+///     infix(left(1), just('*'), |lhs, _, rhs, _| lhs * rhs),
+///     prefix(2, just('-'), |_, rhs: i64, _| -rhs),
+///     // the span of the whole operation, from the operator up to the end of the operand
+///     prefix(2, just('#'), |_, _, extra| {
+///         let span: std::ops::Range<usize> = extra.span();
+///         span.len() as i64
+///     }),
+/// ));
+///
+/// assert_eq!(expr.parse("--2*-3"), Ok(-6));
+/// assert_eq!(expr.parse("#1000*2"), Ok(10));
+/// ```
+pub fn prefix<I, O, E, A, F, Op>(power: u16, op: A, fold: F) -> Prefix<A, F, Op, I, O, E>
+where
+        I: InputType,
+        E: ParserExtras<I>,
+        A: Parser<I, Op, E>,
+        F: for<'input, 'parse> Fn(Op, O, &mut MapExtra<'input, 'parse, I, E>) -> O,
+{
+        Prefix {
+                op,
+                power,
+                fold,
+                phantom: EmptyPhantom::new(),
+        }
+}
+
+impl<I, O, E, A, F, Op> Operator<I, O, E> for Prefix<A, F, Op, I, O, E>
+where
+        I: InputType,
+        E: ParserExtras<I>,
+        A: Parser<I, Op, E>,
+        F: for<'input, 'parse> Fn(Op, O, &mut MapExtra<'input, 'parse, I, E>) -> O,
+{
+        fn parse_prefix<M: Mode>(
+                &self,
+                input: &mut Input<I, E>,
+                operand: &Operand<'_, I, O, E, M>,
+        ) -> Option<M::Output<O>> {
+                let before = input.save();
+                let result = self
+                        .op
+                        .go::<M>(input)
+                        .and_then(|op| operand(input, u32::from(self.power)).map(|rhs| (op, rhs)));
+                match result {
+                        Ok((op, rhs)) => Some(M::combine(op, rhs, |op, rhs| {
+                                (self.fold)(
+                                        op,
+                                        rhs,
+                                        &mut MapExtra {
+                                                start: before.offset,
+                                                end: None,
+                                                input,
+                                        },
+                                )
+                        })),
+                        Err(error) => {
+                                input.add_alt(error);
+                                input.rewind(before);
+                                None
+                        }
+                }
+        }
+
+        fn parse_postfix_or_infix<M: Mode>(
+                &self,
+                _: &mut Input<I, E>,
+                _: I::Offset,
+                lhs: M::Output<O>,
+                _: u32,
+                _: &Operand<'_, I, O, E, M>,
+        ) -> PResult<I, Folded<M, O>, E> {
+                Ok(Err(lhs))
+        }
+}
+
+/// See [`postfix`].
+pub struct Postfix<A, F, Op, I, O, E> {
+        pub(crate) op: A,
+        pub(crate) power: u16,
+        pub(crate) fold: F,
+        pub(crate) phantom: EmptyPhantom<(Op, I, O, E)>,
+}
+
+impl<A: Clone, F: Clone, Op, I, O, E> Clone for Postfix<A, F, Op, I, O, E> {
+        fn clone(&self) -> Self {
+                Self {
+                        op: self.op.clone(),
+                        power: self.power,
+                        fold: self.fold.clone(),
+                        phantom: self.phantom,
+                }
+        }
+}
+
+/// A postfix operator with the binding power `power`, parsed with `op` after its operand.
+/// The operand and the output of `op` are combined by `fold`.
+///
+/// # Example
+/// ```
+/// # use aott::prelude::*;
+/// use aott::pratt::{postfix, prefix};
+/// let atom = text::ascii::ident::<_, _, extra::Err<&str>>.map(str::to_owned);
+/// let expr = atom.pratt((
+///     prefix(1, just('&'), |_, rhs, _| format!("&({rhs})")),
+///     postfix(2, just('?'), |lhs, _, _| format!("({lhs})?")),
+///     postfix(0, just('!'), |lhs, _, _| format!("({lhs})!")),
+/// ));
+///
+/// assert_eq!(expr.parse("&x?!"), Ok("(&((x)?))!".to_owned()));
+/// ```
+pub fn postfix<I, O, E, A, F, Op>(power: u16, op: A, fold: F) -> Postfix<A, F, Op, I, O, E>
+where
+        I: InputType,
+        E: ParserExtras<I>,
+        A: Parser<I, Op, E>,
+        F: for<'input, 'parse> Fn(O, Op, &mut MapExtra<'input, 'parse, I, E>) -> O,
+{
+        Postfix {
+                op,
+                power,
+                fold,
+                phantom: EmptyPhantom::new(),
+        }
+}
+
+impl<I, O, E, A, F, Op> Operator<I, O, E> for Postfix<A, F, Op, I, O, E>
+where
+        I: InputType,
+        E: ParserExtras<I>,
+        A: Parser<I, Op, E>,
+        F: for<'input, 'parse> Fn(O, Op, &mut MapExtra<'input, 'parse, I, E>) -> O,
+{
+        fn parse_prefix<M: Mode>(
+                &self,
+                _: &mut Input<I, E>,
+                _: &Operand<'_, I, O, E, M>,
+        ) -> Option<M::Output<O>> {
+                None
+        }
+
+        fn parse_postfix_or_infix<M: Mode>(
+                &self,
+                input: &mut Input<I, E>,
+                start: I::Offset,
+                lhs: M::Output<O>,
+                min_power: u32,
+                _: &Operand<'_, I, O, E, M>,
+        ) -> PResult<I, Folded<M, O>, E> {
+                if u32::from(self.power) < min_power {
+                        return Ok(Err(lhs));
+                }
+                let before = input.save();
+                let Ok(op) = self.op.go::<M>(input) else {
+                        input.rewind(before);
+                        return Ok(Err(lhs));
+                };
+                Ok(Ok(M::combine(lhs, op, |lhs, op| {
+                        (self.fold)(
+                                lhs,
+                                op,
+                                &mut MapExtra {
+                                        start,
+                                        end: None,
+                                        input,
+                                },
+                        )
+                })))
+        }
+}
+
+macro_rules! impl_operator_for_tuple {
+    () => {};
+    ($head:ident $($X:ident)*) => {
+        impl_operator_for_tuple!($($X)*);
+        impl_operator_for_tuple!(~ $head $($X)*);
+    };
+    (~ $($X:ident)+) => {
+        #[allow(non_snake_case)]
+        impl<I, O, E, $($X),*> Operator<I, O, E> for ($($X,)*)
+        where
+            I: InputType,
+            E: ParserExtras<I>,
+            $($X: Operator<I, O, E>),*
+        {
+            fn parse_prefix<M: Mode>(
+                &self,
+                input: &mut Input<I, E>,
+                operand: &Operand<'_, I, O, E, M>,
+            ) -> Option<M::Output<O>> {
+                let ($($X,)*) = self;
+                $(
+                    if let Some(output) = $X.parse_prefix::<M>(input, operand) {
+                        return Some(output);
+                    }
+                )*
+                None
+            }
+
+            fn parse_postfix_or_infix<M: Mode>(
+                &self,
+                input: &mut Input<I, E>,
+                start: I::Offset,
+                lhs: M::Output<O>,
+                min_power: u32,
+                operand: &Operand<'_, I, O, E, M>,
+            ) -> PResult<I, Folded<M, O>, E> {
+                let ($($X,)*) = self;
+                $(
+                    let lhs = match $X.parse_postfix_or_infix::<M>(input, start, lhs, min_power, operand)? {
+                        Ok(folded) => return Ok(Ok(folded)),
+                        Err(lhs) => lhs,
+                    };
+                )*
+                Ok(Err(lhs))
+            }
+        }
+    };
+}
+
+impl_operator_for_tuple!(A_ B_ C_ D_ E_ F_ G_ H_ I_ J_ K_ L_ M_ N_ O_ P_ Q_ R_ S_ T_ U_ V_ W_ X_ Y_ Z_);
+
+/// See [`Parser::pratt`].
+#[derive(Copy, Clone)]
+pub struct Pratt<A, T> {
+        pub(crate) atom: A,
+        pub(crate) operators: T,
+}
+
+impl<A, T> Pratt<A, T> {
+        /// Parse an expression that only contains operators of at least `min_power`.
+        fn expression<I, O, E, M>(
+                &self,
+                input: &mut Input<I, E>,
+                min_power: u32,
+        ) -> Result<M::Output<O>, E::Error>
+        where
+                I: InputType,
+                E: ParserExtras<I>,
+                A: Parser<I, O, E>,
+                T: Operator<I, O, E>,
+                M: Mode,
+        {
+                let start = input.offset;
+                let operand = |input: &mut Input<I, E>, power| {
+                        self.expression::<I, O, E, M>(input, power)
+                };
+
+                let alt = input.errors.alt.take();
+                let lhs = match self.operators.parse_prefix::<M>(input, &operand) {
+                        Some(lhs) => Ok(lhs),
+                        None => self.atom.go::<M>(input).map_err(|error| {
+                                // if prefix operators failed too, the error of the one that got the furthest wins
+                                input.add_alt(error);
+                                input.take_alt()
+                        }),
+                };
+                input.errors.alt = alt;
+
+                let mut lhs = lhs?;
+                loop {
+                        match self.operators.parse_postfix_or_infix::<M>(
+                                input, start, lhs, min_power, &operand,
+                        )? {
+                                Ok(folded) => lhs = folded,
+                                Err(lhs) => return Ok(lhs),
+                        }
+                }
+        }
+}
+
+impl<I: InputType, O, E: ParserExtras<I>, A: Parser<I, O, E>, T: Operator<I, O, E>> Parser<I, O, E>
+        for Pratt<A, T>
+{
+        fn go<M: Mode>(&self, input: &mut Input<I, E>) -> Result<M::Output<O>, E::Error> {
+                self.expression::<I, O, E, M>(input, 0)
+        }
+
+        go_extra!(O);
+}
+
+#[cfg(test)]
+mod test {
+        use crate::extra::{RichPattern, Simple};
+        use crate::pratt::{infix, left, postfix, prefix};
+        use crate::prelude::*;
+
+        fn expr<'a>() -> impl Parser<&'a str, i64, extra::Err<&'a str>> {
+                let atom = text::int(10).map(|n: &str| n.parse::<i64>().unwrap());
+                atom.pratt((
+                        infix(left(1), just('+'), |lhs, _, rhs, _| lhs + rhs),
+                        infix(left(2), just('*'), |lhs, _, rhs, _| lhs * rhs),
+                        prefix(3, just('-'), |_, rhs: i64, _| -rhs),
+                        postfix(4, just('!'), |lhs: i64, _, _| (1..=lhs).product()),
+                ))
+        }
+
+        fn expected_operand(span: std::ops::Range<usize>, found: char) -> Simple<&'static str> {
+                Simple::Expected {
+                        span,
+                        expected: vec![
+                                RichPattern::Token('-'),
+                                RichPattern::Label("digit with radix 10".into()),
+                        ],
+                        found: Some(found),
+                        context: vec![],
+                }
+        }
+
+        #[test]
+        fn bad_right_hand_operand() {
+                assert_eq!(expr().parse_all("1+x"), Err(expected_operand(2..3, 'x')));
+                assert_eq!(
+                        expr().parse_all("1*2+3*x"),
+                        Err(expected_operand(6..7, 'x'))
+                );
+                assert_eq!(
+                        expr().then_ignore(just(';')).parse("1+x;"),
+                        Err(expected_operand(2..3, 'x'))
+                );
+                assert_eq!(
+                        expr().parse_prefix("1+x").unwrap_err().to_string(),
+                        "expected '-' or digit with radix 10 at 2..3, but found 'x'"
+                );
+        }
+
+        #[test]
+        fn bad_operand_before_the_end() {
+                // the unconsumed input starts further than the failed operand
+                assert_eq!(
+                        expr().parse_all("1+2 x"),
+                        Err(Simple::ExpectedEOF {
+                                found: ' ',
+                                span: 3..5,
+                                context: vec![]
+                        })
+                );
+        }
+
+        #[test]
+        fn check_mode() {
+                assert_eq!(expr().slice().parse_all("-3!*2+1"), Ok("-3!*2+1"));
+                assert_eq!(expr().slice().parse_prefix("1+2 *"), Ok(("1+2", " *")));
+                assert!(expr().slice().parse_prefix("1+2*").is_err());
+                assert_eq!(
+                        expr().slice().parse_all("1+-x"),
+                        Err(expected_operand(3..4, 'x'))
+                );
+        }
+}
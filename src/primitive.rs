@@ -14,7 +14,9 @@ use crate::{
 mod choice;
 mod filter;
 mod just;
+mod label;
 mod map;
+mod memoized;
 mod recursive;
 mod sequence;
 mod take;
@@ -24,7 +26,9 @@ use aott_derive::parser;
 pub use choice::*;
 pub use filter::*;
 pub use just::*;
+pub use label::*;
 pub use map::*;
+pub use memoized::*;
 pub use recursive::*;
 pub use sequence::*;
 pub use take::*;
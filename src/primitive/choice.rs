@@ -7,15 +7,12 @@ pub struct Choice<T> {
 
 /// Parse using a tuple of many parsers, producing the output of the first to successfully parse.
 ///
-/// This primitive has a twofold improvement over a chain of [`Parser::or`] calls:
+/// This primitive has an improvement over a chain of [`Parser::or`] calls:
+/// Rust's trait solver seems to resolve the [`Parser`] impl for this type much faster, significantly reducing
+/// compilation times. This quality makes this parser ideal for lexers.
 ///
-/// - Rust's trait solver seems to resolve the [`Parser`] impl for this type much faster, significantly reducing
-///   compilation times.
-///
-/// - Parsing is likely a little faster in some cases because the resulting parser is 'less careful' about error
-///   routing, and doesn't perform the same fine-grained error prioritization that [`Parser::or`] does.
-///
-/// These qualities make this parser ideal for lexers.
+/// If all of the parsers fail, the error of the one that got the furthest into the input is returned,
+/// and errors of parsers that failed at the same place are combined with [`Error::merge`], just like [`Parser::or`] does.
 ///
 /// The output type of this parser is the output type of the inner parsers.
 ///
@@ -62,7 +59,7 @@ macro_rules! impl_choice_for_tuple {
         impl_choice_for_tuple!(~ $head $($X)*);
     };
     (~ $Head:ident $($X:ident)+) => {
-        #[allow(unused_variables, non_snake_case, unused_assignments)]
+        #[allow(unused_variables, non_snake_case)]
         impl<I, E, $Head, $($X),*, O> Parser<I, O, E> for Choice<($Head, $($X,)*)>
         where
             I: InputType,
@@ -72,44 +69,48 @@ macro_rules! impl_choice_for_tuple {
         {
             #[inline]
             fn parse_with(&self, inp: &mut Input<I, E>) -> PResult<I, O, E> {
-                let mut error: E::Error;
                 let before = inp.save();
+                let alt = inp.errors.alt.take();
 
                 let Choice { parsers: ($Head, $($X,)*) } = self;
 
                 match $Head.parse_with(inp) {
-                    Ok(out) => return Ok(out),
-                    Err(e) => { inp.rewind(before); error = e }
+                    Ok(out) => { inp.errors.alt = alt; return Ok(out) }
+                    Err(e) => { inp.add_alt(e); inp.rewind(before) }
                 }
 
                 $(
                     match $X.parse_with(inp) {
-                        Ok(out) => return Ok(out),
-                        Err(e) => { inp.rewind(before); error = e }
+                        Ok(out) => { inp.errors.alt = alt; return Ok(out) }
+                        Err(e) => { inp.add_alt(e); inp.rewind(before) }
                     }
                 )*
 
+                let error = inp.take_alt();
+                inp.errors.alt = alt;
                 Err(error)
             }
             #[inline]
             fn check_with(&self, inp: &mut Input<I, E>) -> PResult<I, (), E> {
-                let mut error: E::Error;
                 let before = inp.save();
+                let alt = inp.errors.alt.take();
 
                 let Choice { parsers: ($Head, $($X,)*) } = self;
 
                 match $Head.check_with(inp) {
-                    Ok(()) => return Ok(()),
-                    Err(e) => { inp.rewind(before); error = e }
+                    Ok(()) => { inp.errors.alt = alt; return Ok(()) }
+                    Err(e) => { inp.add_alt(e); inp.rewind(before) }
                 }
 
                 $(
                     match $X.check_with(inp) {
-                        Ok(()) => return Ok(()),
-                        Err(e) => { inp.rewind(before); error = e }
+                        Ok(()) => { inp.errors.alt = alt; return Ok(()) }
+                        Err(e) => { inp.add_alt(e); inp.rewind(before) }
                     }
                 )*
 
+                let error = inp.take_alt();
+                inp.errors.alt = alt;
                 Err(error)
             }
 
@@ -136,3 +137,76 @@ macro_rules! impl_choice_for_tuple {
 }
 
 impl_choice_for_tuple!(A_ B_ C_ D_ E_ F_ G_ H_ I_ J_ K_ L_ M_ N_ O_ P_ Q_ R_ S_ T_ U_ V_ W_ X_ Y_ Z_);
+
+#[cfg(test)]
+mod test {
+        use crate::{extra::Simple, prelude::*};
+
+        #[test]
+        fn furthest_error_wins() {
+                let parser = choice((just::<_, _, extra::Err<&str>>("abc"), just("b")));
+                assert_eq!(
+                        parser.parse("abx"),
+                        Err(Simple::ExpectedTokenFound {
+                                span: 2..3,
+                                expected: vec!['c'],
+                                found: 'x',
+                                context: vec![],
+                        })
+                );
+
+                let parser = just::<_, _, extra::Err<&str>>("b").or(just("abc"));
+                assert_eq!(
+                        parser.parse("abx"),
+                        Err(Simple::ExpectedTokenFound {
+                                span: 2..3,
+                                expected: vec!['c'],
+                                found: 'x',
+                                context: vec![],
+                        })
+                );
+        }
+
+        #[test]
+        fn errors_at_same_offset_are_merged() {
+                let parser = choice((just::<_, _, extra::Err<&str>>('a'), just('b'), just('c')));
+                assert_eq!(
+                        parser.parse("x"),
+                        Err(Simple::ExpectedTokenFound {
+                                span: 0..1,
+                                expected: vec!['a', 'b', 'c'],
+                                found: 'x',
+                                context: vec![],
+                        })
+                );
+        }
+
+        // errors of alternatives that failed before one succeeded don't leak into later errors
+        #[test]
+        fn succeeded_alternatives_forget_errors() {
+                let parser = just::<_, _, extra::Err<&str>>('a')
+                        .or(just('b'))
+                        .then(just('c'));
+                assert_eq!(
+                        parser.parse("bx"),
+                        Err(Simple::ExpectedTokenFound {
+                                span: 1..2,
+                                expected: vec!['c'],
+                                found: 'x',
+                                context: vec![],
+                        })
+                );
+
+                let parser =
+                        choice((just::<_, _, extra::Err<&str>>('a'), just('b'))).then(just('c'));
+                assert_eq!(
+                        parser.parse("bx"),
+                        Err(Simple::ExpectedTokenFound {
+                                span: 1..2,
+                                expected: vec!['c'],
+                                found: 'x',
+                                context: vec![],
+                        })
+                );
+        }
+}
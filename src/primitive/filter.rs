@@ -3,10 +3,11 @@ use std::marker::PhantomData;
 use alloc::borrow::Cow;
 
 use crate::{
-        error::{Filtering, LabelError},
+        error::{Error, Filtering, LabelError},
+        go_extra,
         input::{Input, InputType},
         parser::*,
-        pfn_type, PResult,
+        pfn_type, EmptyPhantom, PResult,
 };
 
 fn filter_impl<
@@ -143,8 +144,246 @@ pub fn rewind<I: InputType, O, E: ParserExtras<I>, A: Parser<I, O, E>>(parser: A
         Rewind(parser)
 }
 
+/// See [`not`].
+pub struct Not<A, O>(pub(crate) A, pub(crate) EmptyPhantom<O>);
+
+impl<A: Clone, O> Clone for Not<A, O> {
+        fn clone(&self) -> Self {
+                Self(self.0.clone(), self.1)
+        }
+}
+
+impl<I: InputType, O, E: ParserExtras<I>, A: Parser<I, O, E>> Parser<I, (), E> for Not<A, O> {
+        fn go<M: Mode>(&self, input: &mut Input<I, E>) -> Result<M::Output<()>, E::Error> {
+                let before = input.save();
+                let last_read = input.last_read;
+                let result = self.0.go::<Check>(input);
+                let end = input.offset;
+                input.rewind(before);
+
+                if result.is_err() {
+                        input.last_read = last_read;
+                        return Ok(M::bind(|| ()));
+                }
+                let found = input.next_or_none();
+                // the error covers what the parser matched, or at least the token it was found at
+                let span = input.input.span(before.offset..end.max(input.offset));
+                input.rewind(before);
+                Err(Error::expected_token_found_or_eof(span, vec![], found))
+        }
+
+        go_extra!(());
+}
+
+/// Negative lookahead: succeeds without consuming any input if `parser` fails,
+/// and fails with an "unexpected" error, located at what `parser` matched, if it succeeds.
+///
+/// # Example
+/// ```
+/// # use aott::prelude::*;
+/// use aott::extra::Simple;
+/// // `-` that isn't the start of `->`
+/// let minus = just::<_, _, extra::Err<&str>>('-').then_ignore(not(just('>')));
+///
+/// assert_eq!(minus.parse_prefix("-1"), Ok(('-', "1")));
+/// assert_eq!(
+///     minus.parse("->"),
+///     Err(Simple::ExpectedTokenFound { span: 1..2, expected: vec![], found: '>', context: vec![] })
+/// );
+///
+/// // a keyword that isn't the start of a longer identifier
+/// let keyword = just::<_, _, extra::Err<&str>>("let").then_ignore(not(text::ascii::ident));
+/// assert_eq!(keyword.parse_prefix("let x"), Ok(("let", " x")));
+/// assert!(keyword.parse("letter").is_err());
+/// ```
+#[must_use]
+pub fn not<I: InputType, O, E: ParserExtras<I>, A: Parser<I, O, E>>(parser: A) -> Not<A, O> {
+        Not(parser, EmptyPhantom::new())
+}
+
+/// See [`lookbehind`].
+pub struct Lookbehind<A>(pub(crate) A);
+
+impl<A: Clone> Clone for Lookbehind<A> {
+        fn clone(&self) -> Self {
+                Self(self.0.clone())
+        }
+}
+
+impl<I: InputType, O, E: ParserExtras<I>, A: Parser<I, O, E>> Parser<I, O, E> for Lookbehind<A> {
+        fn go<M: Mode>(&self, input: &mut Input<I, E>) -> Result<M::Output<O>, E::Error> {
+                let before = input.save();
+                let last_read = input.last_read;
+                let prev = input.input.prev(before.offset);
+                if prev == before.offset {
+                        // there's no token before the start of the input, so fail with what `parser`
+                        // expected of the first one instead, or with an unexpected first one if it matches
+                        let result = self.0.go::<Check>(input);
+                        input.rewind(before);
+                        input.last_read = last_read;
+                        result?;
+                        let span = input.input.span(before.offset..before.offset);
+                        return Err(Error::expected_token_found_or_eof(
+                                span,
+                                vec![],
+                                input.peek().ok(),
+                        ));
+                }
+
+                input.offset = prev;
+                let result = self.0.go::<M>(input);
+                let end = input.offset;
+                input.rewind(before);
+                input.last_read = last_read;
+                let output = result?;
+                if end != before.offset {
+                        // `parser` must match the token before, and nothing else
+                        let (from, to) = if end < before.offset {
+                                (end, before.offset)
+                        } else {
+                                (before.offset, end)
+                        };
+                        // SAFETY: both offsets were returned by the input
+                        let found = unsafe { input.input.next(from) }.1;
+                        let span = input.input.span(from..to);
+                        return Err(Error::expected_token_found_or_eof(span, vec![], found));
+                }
+                Ok(output)
+        }
+
+        go_extra!(O);
+}
+
+/// Lookbehind: parses the token right before the current position with `parser`, without consuming any input.
+///
+/// Only that single token is looked at, so `parser` must match exactly one token.
+/// At the start of the input, where there is no token before, it fails with the error `parser` gives
+/// for the first token instead.
+///
+/// # Example
+/// ```
+/// # use aott::prelude::*;
+/// // `/` only starts a regex if it doesn't follow an operand
+/// let operand = filter::<_, extra::Err<&str>, _>(
+///     |c: &char| c.is_alphanumeric() || *c == ')',
+///     filtering("operand"),
+/// );
+/// let regex_start = not(lookbehind(operand)).ignore_then(just('/'));
+///
+/// assert!(regex_start.parse("/").is_ok());
+///
+/// let after_char = any.ignore_then(regex_start);
+/// assert!(after_char.parse("→/").is_ok());
+/// assert!(after_char.parse("é/").is_err());
+/// assert!(after_char.parse(")/").is_err());
+/// ```
+#[must_use]
+pub fn lookbehind<I: InputType, O, E: ParserExtras<I>, A: Parser<I, O, E>>(
+        parser: A,
+) -> Lookbehind<A> {
+        Lookbehind(parser)
+}
+
+/// See [`Parser::and_is`].
+pub struct AndIs<A, B, OB>(pub(crate) A, pub(crate) B, pub(crate) EmptyPhantom<OB>);
+
+impl<A: Clone, B: Clone, OB> Clone for AndIs<A, B, OB> {
+        fn clone(&self) -> Self {
+                Self(self.0.clone(), self.1.clone(), self.2)
+        }
+}
+
+impl<I: InputType, O, E: ParserExtras<I>, A: Parser<I, O, E>, OB, B: Parser<I, OB, E>>
+        Parser<I, O, E> for AndIs<A, B, OB>
+{
+        fn go<M: Mode>(&self, input: &mut Input<I, E>) -> Result<M::Output<O>, E::Error> {
+                let before = input.save();
+                let err_count = input.errors.secondary.len();
+                let output = self.0.go::<M>(input)?;
+                let after = input.save();
+                let last_read = input.last_read;
+
+                // keep the secondary errors of the first parser, but not those of the lookahead
+                let secondary = input.errors.secondary.split_off(err_count);
+                input.rewind(before);
+                let result = self.1.go::<Check>(input);
+                input.rewind(before);
+                input.errors.secondary.extend(secondary);
+                result?;
+
+                input.rewind(after);
+                input.last_read = last_read;
+                Ok(output)
+        }
+
+        go_extra!(O);
+}
+
 /// Creates a [`Filtering`]
 #[must_use]
 pub fn filtering(what: impl Into<Cow<'static, str>>) -> Filtering {
         Filtering(what.into())
 }
+
+#[cfg(test)]
+mod test {
+        use crate::extra::Simple;
+        use crate::prelude::*;
+        use crate::text::CharLabel;
+
+        #[test]
+        fn lookbehind_at_the_start() {
+                let parser = lookbehind(filter::<_, extra::Err<&str>, _>(
+                        |c: &char| c.is_ascii_digit(),
+                        CharLabel::ExpectedDigit(10),
+                ))
+                .ignore_then(any);
+                assert_eq!(
+                        parser.parse("x"),
+                        Err(Simple::Text {
+                                span: 0..1,
+                                error: CharLabel::ExpectedDigit(10),
+                                last_token: Some('x'),
+                                context: vec![],
+                        })
+                );
+                // a digit would be fine before, but not where it is
+                assert_eq!(
+                        parser.parse("1"),
+                        Err(Simple::ExpectedTokenFound {
+                                span: 0..0,
+                                expected: vec![],
+                                found: '1',
+                                context: vec![],
+                        })
+                );
+        }
+
+        #[test]
+        fn lookbehind_must_match_the_token_before() {
+                let any = any::<_, extra::Err<&str>>;
+                let letters = text::ascii::ident;
+                assert_eq!(
+                        any.ignore_then(lookbehind(letters)).parse_prefix("ab"),
+                        Err(Simple::ExpectedTokenFound {
+                                span: 1..2,
+                                expected: vec![],
+                                found: 'b',
+                                context: vec![],
+                        })
+                );
+                assert_eq!(
+                        any.ignore_then(lookbehind(just(""))).parse_prefix("ab"),
+                        Err(Simple::ExpectedTokenFound {
+                                span: 0..1,
+                                expected: vec![],
+                                found: 'a',
+                                context: vec![],
+                        })
+                );
+                assert_eq!(
+                        any.ignore_then(lookbehind(any)).parse_prefix("ab"),
+                        Ok(('a', "b"))
+                );
+        }
+}
@@ -0,0 +1,195 @@
+use alloc::borrow::Cow;
+
+use crate::{
+        error::{LabelError, Located},
+        go_extra,
+        input::Marker,
+};
+
+use super::*;
+
+/// See [`Parser::labelled`].
+#[derive(Copy, Clone)]
+pub struct Labelled<A, L> {
+        pub(crate) parser: A,
+        pub(crate) label: L,
+}
+
+impl<I: InputType, O, E: ParserExtras<I>, A: Parser<I, O, E>, L: Clone> Parser<I, O, E>
+        for Labelled<A, L>
+where
+        E::Error: LabelError<I, L>,
+{
+        fn go<M: Mode>(&self, input: &mut Input<I, E>) -> Result<M::Output<O>, E::Error> {
+                let before = input.save();
+                self.parser
+                        .go::<M>(input)
+                        .map_err(|error| relabel(input, before, self.label.clone(), error))
+        }
+
+        go_extra!(O);
+}
+
+/// Replaces `error` with an error saying that `label` was expected, but only if the parser that produced it
+/// failed without consuming any input since `before`. Otherwise, `error` is returned as-is.
+///
+/// This is what [`Parser::labelled`] and `#[parser(label)]` use under the hood.
+#[doc(hidden)]
+pub fn relabel<I: InputType, E: ParserExtras<I>, L>(
+        input: &mut Input<I, E>,
+        before: Marker<I>,
+        label: L,
+        error: E::Error,
+) -> E::Error
+where
+        E::Error: LabelError<I, L>,
+{
+        if input.last_read > before.offset {
+                return error;
+        }
+
+        input.rewind(before);
+        let found = input.next_or_none();
+        let error = LabelError::from_label(input.span_since(before.offset), label, found);
+        input.rewind(before);
+        error
+}
+
+/// See [`Parser::in_context`].
+#[derive(Clone)]
+pub struct InContext<A> {
+        pub(crate) parser: A,
+        pub(crate) label: Cow<'static, str>,
+}
+
+impl<I: InputType, O, E: ParserExtras<I>, A: Parser<I, O, E>> Parser<I, O, E> for InContext<A> {
+        fn go<M: Mode>(&self, input: &mut Input<I, E>) -> Result<M::Output<O>, E::Error> {
+                let before = input.offset;
+                self.parser.go::<M>(input).map_err(|error| {
+                        // the frame spans from where the wrapped parser started up to where it failed
+                        let end = if input.last_read > before {
+                                input.last_read
+                        } else {
+                                before
+                        };
+                        error.in_context(Located {
+                                pos: input.input.span(before..end),
+                                err: self.label.clone(),
+                        })
+                })
+        }
+
+        go_extra!(O);
+}
+
+#[cfg(test)]
+mod test {
+        use crate::{
+                derive::parser,
+                extra::{Rich, Simple},
+                prelude::*,
+        };
+
+        #[parser(label)]
+        fn pair(input: &str) -> (char, char) {
+                (just('('), just(')')).parse_with(input)
+        }
+
+        #[parser(label = "an assignment")]
+        fn assignment(input: &str) -> &'a str {
+                // `?` and `return` inside of the body are labelled too
+                let name = text::ascii::ident.parse_with(input)?;
+                if name == "let" {
+                        return Err(Simple::Filtering {
+                                span: 0..3,
+                                label: filtering("a name"),
+                                last_token: None,
+                                context: vec![],
+                        });
+                }
+                just('=').parse_with(input)?;
+                Ok(name)
+        }
+
+        // `label` comes after `extras`, so all of the arguments must be looked at
+        #[parser(extras = "extra::Err<&'a str, Rich<&'a str>>", label)]
+        fn rich_pair(input: &str) -> (char, char) {
+                (just('('), just(')')).parse_with(input)
+        }
+
+        #[test]
+        fn parser_label_uses_function_name() {
+                assert_eq!(pair.parse("()"), Ok(('(', ')')));
+                assert_eq!(
+                        pair.parse("x"),
+                        Err(Simple::Filtering {
+                                span: 0..1,
+                                label: filtering("pair"),
+                                last_token: Some('x'),
+                                context: vec![],
+                        })
+                );
+        }
+
+        #[test]
+        fn parser_label_after_extras() {
+                assert_eq!(
+                        rich_pair.parse("x").unwrap_err().to_string(),
+                        "expected rich_pair at 0..1, but found 'x'"
+                );
+                assert_eq!(
+                        rich_pair.parse("(x").unwrap_err().to_string(),
+                        "expected ')' at 1..2, but found 'x'"
+                );
+        }
+
+        #[test]
+        fn parser_label_keeps_error_after_consuming() {
+                assert_eq!(
+                        pair.parse("(x"),
+                        Err(Simple::ExpectedTokenFound {
+                                span: 1..2,
+                                expected: vec![')'],
+                                found: 'x',
+                                context: vec![],
+                        })
+                );
+                assert_eq!(
+                        assignment.parse("x+"),
+                        Err(Simple::ExpectedTokenFound {
+                                span: 1..2,
+                                expected: vec!['='],
+                                found: '+',
+                                context: vec![],
+                        })
+                );
+        }
+
+        #[test]
+        fn parser_label_with_string() {
+                assert_eq!(assignment.parse("x="), Ok("x"));
+                assert_eq!(
+                        assignment.parse("1"),
+                        Err(Simple::Filtering {
+                                span: 0..1,
+                                label: filtering("an assignment"),
+                                last_token: Some('1'),
+                                context: vec![],
+                        })
+                );
+        }
+
+        #[test]
+        fn parser_label_relabels_early_return() {
+                // the early return failed after consuming `let`, so it is kept
+                assert_eq!(
+                        assignment.parse("let"),
+                        Err(Simple::Filtering {
+                                span: 0..3,
+                                label: filtering("a name"),
+                                last_token: None,
+                                context: vec![],
+                        })
+                );
+        }
+}
@@ -1,18 +1,25 @@
 use std::marker::PhantomData;
 
-use crate::input::SliceInput;
+use crate::{
+        go_extra,
+        input::{SliceInput, Spanned},
+        parser::Check,
+};
 
 use super::*;
 
 pub struct MapExtra<'input, 'parse, I: InputType, E: ParserExtras<I>> {
         pub(crate) start: I::Offset,
+        /// Where the span ends, if not at the current offset.
+        pub(crate) end: Option<I::Offset>,
         #[doc(hidden)]
         pub input: &'input mut Input<'parse, I, E>,
 }
 
 impl<'input, 'parse, I: InputType, E: ParserExtras<I>> MapExtra<'input, 'parse, I, E> {
         pub fn span(&self) -> I::Span {
-                self.input.span_since(self.start)
+                let end = self.end.unwrap_or(self.input.offset);
+                self.input.input.span(self.start..end)
         }
 
         pub fn slice(&self) -> I::Slice
@@ -25,6 +32,11 @@ impl<'input, 'parse, I: InputType, E: ParserExtras<I>> MapExtra<'input, 'parse,
         pub fn context(&self) -> &E::Context {
                 self.input.context()
         }
+
+        /// Pairs `value` with the [`span`](Self::span) of the parsed input.
+        pub fn spanned<T>(&self, value: T) -> Spanned<T, I::Span> {
+                Spanned::new(value, self.span())
+        }
 }
 
 pub struct Or<A, B>(pub(crate) A, pub(crate) B);
@@ -33,21 +45,24 @@ where
         A: Parser<I, O, E>,
         B: Parser<I, O, E>,
 {
-        fn check_with(&self, input: &mut Input<I, E>) -> PResult<I, (), E> {
+        fn go<M: Mode>(&self, input: &mut Input<I, E>) -> Result<M::Output<O>, E::Error> {
                 let befunge = input.save();
-                self.0.check_with(input).or_else(|_| {
-                        input.rewind(befunge);
-                        self.1.check_with(input)
-                })
-        }
+                let alt = input.errors.alt.take();
 
-        fn parse_with(&self, input: &mut Input<I, E>) -> PResult<I, O, E> {
-                let befunge = input.save();
-                self.0.parse_with(input).or_else(|_| {
+                let result = self.0.go::<M>(input).or_else(|error| {
+                        input.add_alt(error);
                         input.rewind(befunge);
-                        self.1.parse_with(input)
-                })
+                        self.1.go::<M>(input).map_err(|error| {
+                                input.add_alt(error);
+                                input.take_alt()
+                        })
+                });
+
+                input.errors.alt = alt;
+                result
         }
+
+        go_extra!(O);
 }
 
 pub struct Map<A, O, F, U>(
@@ -79,6 +94,87 @@ impl<I: InputType, O, E: ParserExtras<I>, U: Clone, A: Parser<I, O, E>> Parser<I
         }
 }
 
+/// See [`Parser::map_with`].
+pub struct MapWith<A, F, O>(pub(crate) A, pub(crate) F, pub(crate) EmptyPhantom<O>);
+
+impl<A: Clone, F: Clone, O> Clone for MapWith<A, F, O> {
+        fn clone(&self) -> Self {
+                Self(self.0.clone(), self.1.clone(), self.2)
+        }
+}
+
+impl<
+                I: InputType,
+                O,
+                E: ParserExtras<I>,
+                U,
+                F: for<'input, 'parse> Fn(O, &mut MapExtra<'input, 'parse, I, E>) -> U,
+                A: Parser<I, O, E>,
+        > Parser<I, U, E> for MapWith<A, F, O>
+{
+        fn go<M: Mode>(&self, input: &mut Input<I, E>) -> Result<M::Output<U>, E::Error> {
+                let before = input.offset;
+                let out = self.0.go::<M>(input)?;
+                Ok(M::map(out, |out| {
+                        (self.1)(
+                                out,
+                                &mut MapExtra {
+                                        start: before,
+                                        end: None,
+                                        input,
+                                },
+                        )
+                }))
+        }
+
+        go_extra!(U);
+}
+
+/// See [`Parser::spanned`].
+pub struct WithSpan<A, O>(pub(crate) A, pub(crate) EmptyPhantom<O>);
+
+impl<A: Clone, O> Clone for WithSpan<A, O> {
+        fn clone(&self) -> Self {
+                Self(self.0.clone(), self.1)
+        }
+}
+
+impl<I: InputType, O, E: ParserExtras<I>, A: Parser<I, O, E>> Parser<I, (O, I::Span), E>
+        for WithSpan<A, O>
+{
+        fn go<M: Mode>(
+                &self,
+                input: &mut Input<I, E>,
+        ) -> Result<M::Output<(O, I::Span)>, E::Error> {
+                let before = input.offset;
+                let out = self.0.go::<M>(input)?;
+                Ok(M::map(out, |out| (out, input.span_since(before))))
+        }
+
+        go_extra!((O, I::Span));
+}
+
+/// See [`Parser::to_span`].
+pub struct ToSpan<A, O>(pub(crate) A, pub(crate) EmptyPhantom<O>);
+
+impl<A: Clone, O> Clone for ToSpan<A, O> {
+        fn clone(&self) -> Self {
+                Self(self.0.clone(), self.1)
+        }
+}
+
+impl<I: InputType, O, E: ParserExtras<I>, A: Parser<I, O, E>> Parser<I, I::Span, E>
+        for ToSpan<A, O>
+{
+        fn go<M: Mode>(&self, input: &mut Input<I, E>) -> Result<M::Output<I::Span>, E::Error> {
+                let before = input.offset;
+                self.0.go::<Check>(input)?;
+                Ok(M::bind(|| input.span_since(before)))
+        }
+
+        go_extra!(I::Span);
+}
+
 pub struct TryMapWith<A, F, O, U>(
         pub(crate) A,
         pub(crate) F,
@@ -105,6 +201,7 @@ impl<
                                 thing,
                                 &mut MapExtra {
                                         start: befunge,
+                                        end: None,
                                         input,
                                 },
                         )
@@ -119,9 +216,82 @@ impl<
                                 thing,
                                 &mut MapExtra {
                                         start: befunge,
+                                        end: None,
+                                        input,
+                                },
+                        )
+                })
+        }
+}
+
+/// See [`Parser::map_err`].
+#[derive(Copy, Clone)]
+pub struct MapErr<A, F>(pub(crate) A, pub(crate) F);
+impl<I: InputType, O, E: ParserExtras<I>, A: Parser<I, O, E>, F: Fn(E::Error) -> E::Error>
+        Parser<I, O, E> for MapErr<A, F>
+{
+        fn go<M: Mode>(&self, input: &mut Input<I, E>) -> Result<M::Output<O>, E::Error> {
+                self.0.go::<M>(input).map_err(&self.1)
+        }
+
+        go_extra!(O);
+}
+
+/// See [`Parser::map_err_with_span`].
+#[derive(Copy, Clone)]
+pub struct MapErrWithSpan<A, F>(pub(crate) A, pub(crate) F);
+impl<
+                I: InputType,
+                O,
+                E: ParserExtras<I>,
+                A: Parser<I, O, E>,
+                F: for<'input, 'parse> Fn(E::Error, &mut MapExtra<'input, 'parse, I, E>) -> E::Error,
+        > Parser<I, O, E> for MapErrWithSpan<A, F>
+{
+        fn go<M: Mode>(&self, input: &mut Input<I, E>) -> Result<M::Output<O>, E::Error> {
+                let befunge = input.offset;
+                self.0.go::<M>(input).map_err(|error| {
+                        // the span ends where the parser failed, no matter where it left the input
+                        let end = if input.last_read > befunge {
+                                input.last_read
+                        } else {
+                                befunge
+                        };
+                        self.1(
+                                error,
+                                &mut MapExtra {
+                                        start: befunge,
+                                        end: Some(end),
                                         input,
                                 },
                         )
                 })
         }
+
+        go_extra!(O);
+}
+
+/// See [`Parser::or_else`].
+#[derive(Copy, Clone)]
+pub struct OrElse<A, F>(pub(crate) A, pub(crate) F);
+impl<
+                I: InputType,
+                O,
+                E: ParserExtras<I>,
+                A: Parser<I, O, E>,
+                F: Fn(E::Error) -> PResult<I, O, E>,
+        > Parser<I, O, E> for OrElse<A, F>
+{
+        fn go<M: Mode>(&self, input: &mut Input<I, E>) -> Result<M::Output<O>, E::Error> {
+                let befunge = input.save();
+                match self.0.go::<M>(input) {
+                        Ok(out) => Ok(out),
+                        Err(error) => {
+                                input.rewind(befunge);
+                                (self.1)(error).map(|out| M::bind(|| out))
+                        }
+                }
+        }
+
+        go_extra!(O);
 }
@@ -0,0 +1,318 @@
+use std::{
+        any::TypeId,
+        marker::PhantomData,
+        ptr::NonNull,
+        sync::atomic::{AtomicUsize, Ordering},
+};
+
+use hashbrown::HashMap;
+
+use crate::{error::Secondary, go_extra};
+
+use super::*;
+
+/// See [`Parser::memoized`].
+#[derive(Copy, Clone)]
+pub struct Memoized<A> {
+        pub(crate) parser: A,
+        /// Shared with the clones of this parser.
+        pub(crate) id: MemoId,
+}
+
+impl<A> Memoized<A> {
+        pub(crate) fn new(parser: A) -> Self {
+                Self {
+                        parser,
+                        id: MemoId::new(),
+                }
+        }
+}
+
+impl<I: InputType, O: Clone, E: ParserExtras<I>, A: Parser<I, O, E>> Parser<I, O, E> for Memoized<A>
+where
+        E::Error: Clone,
+        I::Span: Clone,
+{
+        fn go<M: Mode>(&self, input: &mut Input<I, E>) -> Result<M::Output<O>, E::Error> {
+                if let Some(result) = replay::<M, I, O, E>(self.id, input) {
+                        return result;
+                }
+
+                let start = input.offset;
+                let err_count = input.errors.secondary.len();
+                let mut output = None;
+                let result = self.parser.go::<M>(input).map(|out| {
+                        M::map(out, |out: O| {
+                                output = Some(out.clone());
+                                out
+                        })
+                });
+                let memo = Memo {
+                        end: input.offset,
+                        last_read: input.last_read,
+                        secondary: input.errors.secondary[err_count..].to_vec(),
+                        result: result.as_ref().map(|_| output).map_err(Clone::clone),
+                };
+                input.memos.table::<I, O, E>(self.id).insert(start, memo);
+                result
+        }
+
+        go_extra!(O);
+}
+
+/// Identifies the memo table of a parser, and of its clones, in [`Input::memos`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct MemoId(usize);
+
+impl MemoId {
+        pub(crate) fn new() -> Self {
+                static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+                Self(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+        }
+}
+
+/// The result of a memoized parser at some offset.
+pub(crate) struct Memo<I: InputType, O, E: ParserExtras<I>> {
+        /// The offset the parser stopped at.
+        pub(crate) end: I::Offset,
+        pub(crate) last_read: I::Offset,
+        /// The secondary errors emitted by the parser.
+        pub(crate) secondary: Vec<Secondary<I::Span, E::Error>>,
+        /// The output of the parser, if it succeeded. It's only `Some` if it was parsed in [`Emit`] mode.
+        pub(crate) result: Result<Option<O>, E::Error>,
+}
+
+/// The memos of a single parser, by the offset they start at.
+pub(crate) type MemoTable<I, O, E> = HashMap<<I as InputType>::Offset, Memo<I, O, E>>;
+
+/// The memo tables of the memoized parsers of a single parse, by [`MemoId`] and type of table
+/// (a parser can have several outputs). They are dropped with the [`Input`] of the parse.
+#[derive(Default)]
+pub(crate) struct MemoTables {
+        tables: HashMap<(MemoId, TypeId), ErasedTable>,
+}
+
+impl std::fmt::Debug for MemoTables {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_struct("MemoTables")
+                        .field("tables", &self.tables.len())
+                        .finish_non_exhaustive()
+        }
+}
+
+/// A `Box<MemoTable<I, O, E>>` with its type erased. [`Any`](std::any::Any) can't be used for that,
+/// because it only holds `'static` types, and outputs may borrow from the input.
+struct ErasedTable {
+        table: NonNull<()>,
+        drop: unsafe fn(NonNull<()>),
+}
+
+impl Drop for ErasedTable {
+        fn drop(&mut self) {
+                // SAFETY: `drop` was made for the type of the table, and is only called once
+                unsafe { (self.drop)(self.table) }
+        }
+}
+
+impl MemoTables {
+        /// The memo table of the parser `id`, for outputs of type `O`.
+        pub(crate) fn table<I: InputType, O, E: ParserExtras<I>>(
+                &mut self,
+                id: MemoId,
+        ) -> &mut MemoTable<I, O, E> {
+                unsafe fn drop_table<T>(table: NonNull<()>) {
+                        drop(unsafe { Box::from_raw(table.cast::<T>().as_ptr()) });
+                }
+
+                let key = (id, type_id::<MemoTable<I, O, E>>());
+                let table = self.tables.entry(key).or_insert_with(|| ErasedTable {
+                        table: NonNull::from(Box::leak(Box::<MemoTable<I, O, E>>::default()))
+                                .cast(),
+                        drop: drop_table::<MemoTable<I, O, E>>,
+                });
+                // SAFETY: the table was created above with the same type, up to lifetimes.
+                //         Those of `I` and `E` are the same for the whole parse, and a parser can only
+                //         have outputs of different lifetimes if they are valid for all of them.
+                unsafe { table.table.cast::<MemoTable<I, O, E>>().as_mut() }
+        }
+}
+
+/// The [`TypeId`] of `T`, which, unlike with [`TypeId::of`], doesn't have to be `'static`:
+/// types that only differ in lifetimes have the same id.
+fn type_id<T: ?Sized>() -> TypeId {
+        trait NonStaticAny {
+                fn type_id(&self) -> TypeId
+                where
+                        Self: 'static;
+        }
+
+        impl<T: ?Sized> NonStaticAny for PhantomData<T> {
+                fn type_id(&self) -> TypeId
+                where
+                        Self: 'static,
+                {
+                        TypeId::of::<T>()
+                }
+        }
+
+        let phantom: &dyn NonStaticAny = &PhantomData::<T>;
+        // SAFETY: lifetimes are erased before code is generated, so `TypeId::of` can't depend on them,
+        //         and the extended reference isn't used for anything else
+        let phantom: &(dyn NonStaticAny + 'static) = unsafe { std::mem::transmute(phantom) };
+        phantom.type_id()
+}
+
+/// Reuse the result the parser `id` memoized at the current offset: move to where the parser stopped,
+/// emit its secondary errors again and return its result.
+///
+/// Returns `None` if there is no memo there, or if it was parsed in check mode and has no output to emit.
+pub(crate) fn replay<M: Mode, I: InputType, O: Clone, E: ParserExtras<I>>(
+        id: MemoId,
+        input: &mut Input<I, E>,
+) -> Option<Result<M::Output<O>, E::Error>>
+where
+        E::Error: Clone,
+        I::Span: Clone,
+{
+        let memo = input.memos.table::<I, O, E>(id).get(&input.offset)?;
+        let result = match &memo.result {
+                Err(error) => Err(error.clone()),
+                Ok(output) => Ok(
+                        M::choose(output, |output| output.clone().ok_or(()), |_| Ok(())).ok()?,
+                ),
+        };
+        input.offset = memo.end;
+        input.last_read = memo.last_read;
+        let secondary = memo.secondary.iter().cloned();
+        input.errors.secondary.extend(secondary);
+        Some(result)
+}
+
+#[cfg(test)]
+mod test {
+        use std::cell::Cell;
+
+        use crate::extra::Rich;
+        use crate::prelude::*;
+        use crate::text::{CharLabel, IdentKind};
+
+        // the repair trials parse an edited input, so they mustn't reuse memos of the unedited one
+        #[test]
+        fn repair_ignores_memos_of_unedited_input() {
+                let keyword = just::<_, _, extra::Err<&str>>("ab").memoized();
+                let statement = (keyword, just(';')).recover_with(repair());
+
+                let result = statement.parse_recovering("a;");
+                assert_eq!(result.output(), Some(&("ab", ';')));
+                assert_eq!(result.secondary().len(), 1);
+        }
+
+        #[test]
+        fn borrowed_output() {
+                let source = String::from("abc def");
+                let ident = text::ascii::ident::<_, _, extra::Err<&str>>.memoized();
+                let parser = choice((
+                        (ident.clone(), just(' '), just("xyz")).map(|(ident, _, _)| ident),
+                        (ident.clone(), just(' '), ident).map(|(ident, _, _)| ident),
+                ));
+                assert_eq!(parser.parse(source.as_str()), Ok("abc"));
+        }
+
+        fn word<'a>(
+                input: &mut Input<&'a str, extra::Err<&'a str>>,
+        ) -> PResult<&'a str, &'a str, extra::Err<&'a str>> {
+                text::ascii::ident(input)
+        }
+
+        // the memos are kept by the input, so the parser isn't tied to the lifetime of one
+        #[test]
+        fn inputs_of_different_lifetimes() {
+                let word = word.memoized();
+                let first = String::from("abc");
+                {
+                        let second = String::from("def");
+                        assert_eq!(word.parse(second.as_str()), Ok("def"));
+                }
+                assert_eq!(word.parse(first.as_str()), Ok("abc"));
+        }
+
+        #[test]
+        fn memo_hit_replays_secondary_errors() {
+                let runs = Cell::new(0);
+                let byte = text::int::<_, _, extra::Err<&str, Rich<&str>>>(10)
+                        .validate(|number: &str, extra, emitter| {
+                                runs.set(runs.get() + 1);
+                                number.parse::<u8>().unwrap_or_else(|_| {
+                                        emitter.emit(Rich::custom(extra.span(), "too big"));
+                                        u8::MAX
+                                })
+                        })
+                        .boxed()
+                        .memoized();
+                let parser = choice((
+                        (byte.clone(), just('+')).map(|(byte, _)| byte),
+                        (byte.clone(), just('-')).map(|(byte, _)| byte),
+                ));
+
+                let result = parser.parse_recovering("300-");
+                assert_eq!(result.output(), Some(&255));
+                assert_eq!(runs.get(), 1);
+                // the secondary error of the first alternative was rewound, and emitted again by the memo
+                let messages: Vec<_> = result
+                        .secondary()
+                        .iter()
+                        .map(|e| e.err.messages())
+                        .collect();
+                assert_eq!(messages, [["too big"]]);
+        }
+
+        #[test]
+        fn check_mode() {
+                let runs = Cell::new(0);
+                let letter = |c: &char| {
+                        runs.set(runs.get() + 1);
+                        c.is_ascii_alphabetic()
+                };
+                let letters = filter::<_, extra::Err<&str>, _>(
+                        letter,
+                        CharLabel::ExpectedIdent(IdentKind::Alphabetic),
+                )
+                .repeated()
+                .at_least(1)
+                .slice()
+                .boxed()
+                .memoized();
+                let parser = choice((
+                        (letters.clone(), just('!')).slice(),
+                        (letters.clone(), just('?')).slice(),
+                ));
+
+                assert_eq!(parser.slice().parse("ab?"), Ok("ab?"));
+                // the second alternative reused the memo, even in check mode
+                assert_eq!(runs.get(), 3);
+
+                // a memo from check mode has no output to reuse, so the parser runs again
+                runs.set(0);
+                let parser = choice((
+                        (letters.clone(), just('!')).slice(),
+                        letters.then_ignore(just('?')),
+                ));
+                assert_eq!(parser.parse("ab?"), Ok("ab"));
+                assert_eq!(runs.get(), 6);
+        }
+
+        #[test]
+        fn separate_parses() {
+                let runs = Cell::new(0);
+                let digit = |c: &char| {
+                        runs.set(runs.get() + 1);
+                        c.is_ascii_digit()
+                };
+                let digit = filter::<_, extra::Err<&str>, _>(digit, CharLabel::ExpectedDigit(10))
+                        .memoized();
+                assert_eq!(digit.parse("1"), Ok('1'));
+                // the memo of the first parse is forgotten
+                assert!(digit.parse("a").is_err());
+                assert_eq!(runs.get(), 2);
+        }
+}
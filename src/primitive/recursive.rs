@@ -1,10 +1,14 @@
 use std::cell::OnceCell;
 
 use crate::{
-        input::InputType,
-        parser::{Parser, ParserExtras},
+        error::{Error, Located},
+        go_extra,
+        input::{Input, InputType},
+        parser::{Mode, Parser, ParserExtras},
 };
 
+use super::memoized::{replay, Memo, MemoId};
+
 type OnceParser<'a, I, O, E> = OnceCell<Box<dyn Parser<I, O, E> + 'a>>;
 
 pub struct Recursive<'a, I: InputType, O, E: ParserExtras<I>>(
@@ -37,7 +41,7 @@ impl<'a, I: InputType, O, E: ParserExtras<I>> Recursive<'a, I, O, E> {
         }
 }
 
-impl<'a, I: InputType, O, E: ParserExtras<I>> Clone for Recursive<'a, I, O, E> {
+impl<I: InputType, O, E: ParserExtras<I>> Clone for Recursive<'_, I, O, E> {
         fn clone(&self) -> Self {
                 Self(match &self.0 {
                         RecursiveImp::Owned(own) => {
@@ -50,7 +54,7 @@ impl<'a, I: InputType, O, E: ParserExtras<I>> Clone for Recursive<'a, I, O, E> {
         }
 }
 
-impl<'a, I: InputType, O, E: ParserExtras<I>> Parser<I, O, E> for Recursive<'a, I, O, E> {
+impl<I: InputType, O, E: ParserExtras<I>> Parser<I, O, E> for Recursive<'_, I, O, E> {
         fn check_with(&self, input: &mut crate::input::Input<I, E>) -> crate::PResult<I, (), E> {
                 self.cell()
                         .get()
@@ -75,3 +79,246 @@ pub fn recursive<'a, I: InputType + 'a, O: 'a, E: ParserExtras<I> + 'a, P: Parse
         rec.define(def(rec.clone()));
         rec
 }
+
+/// See [`left_recursive`].
+pub struct LeftRecursive<'a, I: InputType, O, E: ParserExtras<I>> {
+        parser: Recursive<'a, I, O, E>,
+        /// Shared with the clones of this parser.
+        id: MemoId,
+}
+
+impl<I: InputType, O, E: ParserExtras<I>> Clone for LeftRecursive<'_, I, O, E> {
+        fn clone(&self) -> Self {
+                Self {
+                        parser: self.parser.clone(),
+                        id: self.id,
+                }
+        }
+}
+
+impl<I: InputType, O: Clone, E: ParserExtras<I>> Parser<I, O, E> for LeftRecursive<'_, I, O, E>
+where
+        E::Error: Clone,
+        I::Span: Clone,
+{
+        fn go<M: Mode>(&self, input: &mut Input<I, E>) -> Result<M::Output<O>, E::Error> {
+                if let Some(result) = replay::<M, I, O, E>(self.id, input) {
+                        return result;
+                }
+
+                // the seed: left-recursive calls at this offset fail, so only the other alternatives can match
+                let start = input.save();
+                let err_count = input.errors.secondary.len();
+                let found = input.next_or_none();
+                let seed = Error::expected_token_found_or_eof(
+                        input.span_since(start.offset),
+                        vec![],
+                        found,
+                );
+                input.rewind(start);
+                input.memos.table::<I, O, E>(self.id).insert(
+                        start.offset,
+                        Memo {
+                                end: start.offset,
+                                last_read: start.offset,
+                                secondary: vec![],
+                                result: Err(seed),
+                        },
+                );
+
+                // grow the seed: parse again, with left-recursive calls reusing the last result,
+                // for as long as that gets further into the input
+                let grow = |input: &mut Input<I, E>| {
+                        let result = self.parser.parse_with(input);
+                        let grown = (
+                                result,
+                                input.offset,
+                                input.last_read,
+                                input.errors.secondary[err_count..].to_vec(),
+                        );
+                        input.rewind(start);
+                        grown
+                };
+                // the first result is kept even if it's an error,
+                // which then comes from the base case, or from the seed if there is none
+                let (mut result, mut end, mut last_read, mut secondary) = grow(input);
+                result = result.map_err(|error| {
+                        let end = if last_read > start.offset {
+                                last_read
+                        } else {
+                                start.offset
+                        };
+                        error.in_context(Located {
+                                pos: input.input.span(start.offset..end),
+                                err: "the base case of a left-recursive rule".into(),
+                        })
+                });
+                loop {
+                        let memo = Memo {
+                                end,
+                                last_read,
+                                secondary: secondary.clone(),
+                                result: result.clone().map(Some),
+                        };
+                        input.memos
+                                .table::<I, O, E>(self.id)
+                                .insert(start.offset, memo);
+                        if result.is_err() {
+                                break;
+                        }
+                        let next = grow(input);
+                        if next.0.is_err() || next.1 <= end {
+                                break;
+                        }
+                        (result, end, last_read, secondary) = next;
+                }
+                input.offset = end;
+                input.last_read = last_read;
+                input.errors.secondary.extend(secondary);
+                result.map(|output| M::bind(|| output))
+        }
+
+        go_extra!(O);
+}
+
+/// Like [`recursive`], but the parser can also be left-recursive: it can call itself again
+/// before consuming any input, like in `expr := expr '-' number | number`. Plain [`recursive`] parsers
+/// would recurse forever there.
+///
+/// This works by growing a seed (Warth et al., "Packrat Parsers Can Support Left Recursion"):
+/// at first, the left-recursive calls fail, so only the other alternatives can match.
+/// Then the parser runs again, with the left-recursive calls returning what it parsed the last time,
+/// for as long as that gets further into the input. The results are memoized, like with [`Parser::memoized`],
+/// and shared by the clones of the parser during a parse.
+///
+/// If not even the first parse matches, its error gets a context frame for
+/// "the base case of a left-recursive rule", since that's what failed (or is missing).
+///
+/// A cycle of left recursion must go through a parser created by this function.
+/// Memoized parsers in that cycle are reused while the seed grows, so they can stop it early.
+///
+/// # Example
+/// ```
+/// # use aott::prelude::*;
+/// let expr = left_recursive(|expr| {
+///     let number = || text::int::<_, _, extra::Err<&str>>(10).map(|n: &str| n.parse::<i64>().unwrap());
+///     choice((
+///         (expr, just('-'), number()).map(|(lhs, _, rhs)| lhs - rhs),
+///         number(),
+///     ))
+/// });
+///
+/// // subtraction is left-associative
+/// assert_eq!(expr.parse("10-3-2"), Ok(5));
+/// ```
+pub fn left_recursive<
+        'a,
+        I: InputType + 'a,
+        O: Clone + 'a,
+        E: ParserExtras<I> + 'a,
+        P: Parser<I, O, E> + 'a,
+>(
+        def: impl Fn(LeftRecursive<'a, I, O, E>) -> P + 'a,
+) -> LeftRecursive<'a, I, O, E>
+where
+        E::Error: Clone,
+        I::Span: Clone,
+{
+        let mut parser = LeftRecursive {
+                parser: Recursive::declare(),
+                id: MemoId::new(),
+        };
+        parser.parser.define(def(parser.clone()));
+        parser
+}
+
+#[cfg(test)]
+mod test {
+        use crate::error::Located;
+        use crate::extra::Simple;
+        use crate::prelude::*;
+
+        fn number<'a>() -> impl Parser<&'a str, i64, extra::Err<&'a str>> {
+                text::int(10).map(|n: &str| n.parse::<i64>().unwrap())
+        }
+
+        fn subtraction<'a>() -> LeftRecursive<'a, &'a str, i64, extra::Err<&'a str>> {
+                left_recursive(|expr| {
+                        choice((
+                                (expr, just('-'), number()).map(|(lhs, _, rhs)| lhs - rhs),
+                                number(),
+                        ))
+                })
+        }
+
+        #[test]
+        fn no_matching_base_case() {
+                let expr = subtraction();
+                assert_eq!(
+                        expr.parse("x").unwrap_err().to_string(),
+                        "expected digit with radix 10 at 0..1, but found 'x', \
+                         while parsing the base case of a left-recursive rule at 0..0"
+                );
+                assert!(expr.parse("").is_err());
+                // the seed grows for as long as it can, and the rest is left unparsed
+                assert_eq!(expr.parse_prefix("10-3-x"), Ok((7, "-x")));
+        }
+
+        #[test]
+        fn no_base_case() {
+                let expr = left_recursive(|expr| {
+                        (expr, just('-'), number()).map(|(lhs, _, rhs): (i64, _, _)| lhs - rhs)
+                });
+                // the seed never grows, because the left-recursive call fails on whatever is there
+                assert_eq!(
+                        expr.parse("1-2"),
+                        Err(Simple::ExpectedTokenFound {
+                                span: 0..1,
+                                expected: vec![],
+                                found: '1',
+                                context: vec![Located::at(
+                                        0..0,
+                                        "the base case of a left-recursive rule".into()
+                                )],
+                        })
+                );
+        }
+
+        #[test]
+        fn clones_share_the_rule() {
+                let expr = subtraction();
+                let pair = (expr.clone(), just(','), expr.clone()).map(|(a, _, b)| (a, b));
+                assert_eq!(pair.parse("5-1,9-2-3"), Ok((4, 4)));
+                assert_eq!(expr.parse("3-2"), Ok(1));
+        }
+
+        // a repair trial parses with memo tables of its own, so the rule is grown again inside it
+        #[test]
+        fn recursion_inside_repair() {
+                let expr = left_recursive(|expr| {
+                        let atom = |expr| {
+                                choice((
+                                        number(),
+                                        (just('('), expr, just(')'))
+                                                .recover_with(repair())
+                                                .map(|(_, inner, _)| inner),
+                                ))
+                        };
+                        choice((
+                                (expr.clone(), just('+'), atom(expr.clone()))
+                                        .map(|(lhs, _, rhs)| lhs + rhs),
+                                atom(expr),
+                        ))
+                });
+                let result = expr.parse_recovering("(1");
+                assert_eq!(result.output(), Some(&1));
+                assert_eq!(result.secondary().len(), 1);
+                assert_eq!(expr.parse_recovering("1+(2").output(), Some(&3));
+        }
+
+        #[test]
+        fn check_mode() {
+                let expr = subtraction();
+                assert_eq!(expr.slice().parse_prefix("10-3-2+1"), Ok(("10-3-2", "+1")));
+        }
+}
@@ -1,24 +1,32 @@
 use std::marker::PhantomData;
 
+#[cfg(feature = "error-recovery")]
+use crate::recovery;
 use crate::{
-        container::Seq, error::LabelError, input::SliceInput, iter::IterParser, parser::Check,
+        container::Seq,
+        error::LabelError,
+        go_extra,
+        input::{Marker, SliceInput},
+        iter::IterParser,
+        parser::Check,
         pfn_type,
 };
 
 use super::*;
 
 #[derive(Copy, Clone, Debug)]
-pub struct Repeated<P, O> {
+pub struct Repeated<P, O, R = Stop> {
         pub(crate) parser: P,
         pub(crate) at_least: usize,
         // Slightly evil: should be `Option<usize>`, but we encode `!0` as 'no cap' because it's so large
         pub(crate) at_most: u64,
+        pub(crate) on_error: R,
         pub(crate) phantom: PhantomData<O>,
         #[cfg(debug_assertions)]
         pub(crate) location: std::panic::Location<'static>,
 }
 
-impl<P, O> Repeated<P, O> {
+impl<P, O, R> Repeated<P, O, R> {
         pub fn at_least(self, at_least: usize) -> Self {
                 Self { at_least, ..self }
         }
@@ -35,10 +43,170 @@ impl<P, O> Repeated<P, O> {
                         ..self
                 }
         }
+
+        /// When an item fails after consuming input, record its error in the secondary errors and skip to where
+        /// the next item starts, instead of ending the repetition. Only the items that succeeded are produced.
+        ///
+        /// # Example
+        /// ```
+        /// # use aott::prelude::*;
+        /// let item = (text::ascii::ident::<_, _, extra::Err<&str>>, just(';'));
+        /// let items = item.map(|(name, _)| name).repeated().recover_items().collect::<Vec<_>>();
+        ///
+        /// // `b` is missing its `;`, so it is skipped up to `c`
+        /// let result = items.parse_recovering("a;b c;d;");
+        /// assert_eq!(result.output(), Some(&vec!["a", "c", "d"]));
+        /// assert_eq!(result.secondary()[0].span, 2..4);
+        /// ```
+        #[cfg(feature = "error-recovery")]
+        pub fn recover_items(self) -> Repeated<P, O, SkipItems> {
+                self.on_error(SkipItems)
+        }
+
+        /// Like [`Repeated::recover_items`], but produce an item with `placeholder` from the skipped span
+        /// in place of each one that failed.
+        ///
+        /// # Example
+        /// ```
+        /// # use aott::prelude::*;
+        /// #[derive(Debug, PartialEq)]
+        /// enum Item<'a> {
+        ///     Name(&'a str),
+        ///     Error,
+        /// }
+        ///
+        /// let item = (text::ascii::ident::<_, _, extra::Err<&str>>, just(';'));
+        /// let items = item
+        ///     .map(|(name, _)| Item::Name(name))
+        ///     .repeated()
+        ///     .recover_items_with(|_span| Item::Error)
+        ///     .collect::<Vec<_>>();
+        ///
+        /// let result = items.parse_recovering("a;b c;d;");
+        /// assert_eq!(result.output(), Some(&vec![Item::Name("a"), Item::Error, Item::Name("c"), Item::Name("d")]));
+        /// ```
+        #[cfg(feature = "error-recovery")]
+        pub fn recover_items_with<F>(self, placeholder: F) -> Repeated<P, O, Placeholder<F>> {
+                self.on_error(Placeholder(placeholder))
+        }
+
+        #[cfg(feature = "error-recovery")]
+        fn on_error<R2>(self, on_error: R2) -> Repeated<P, O, R2> {
+                Repeated {
+                        parser: self.parser,
+                        at_least: self.at_least,
+                        at_most: self.at_most,
+                        on_error,
+                        phantom: PhantomData,
+                        #[cfg(debug_assertions)]
+                        location: self.location,
+                }
+        }
+
+        /// Whether failed items are skipped, because of [`Repeated::recover_items`] or the recovery config of `E`.
+        fn skips<I: InputType, E: ParserExtras<I>>() -> bool
+        where
+                R: OnItemError<I::Span, O>,
+        {
+                #[cfg(feature = "error-recovery")]
+                if E::RECOVERY.repeated {
+                        return true;
+                }
+                R::SKIP
+        }
+}
+
+/// What [`Repeated`] and [`SeparatedBy`] do when one of their items fails after consuming input.
+pub trait OnItemError<S, O> {
+        /// Whether the failed item is skipped, instead of ending the sequence.
+        const SKIP: bool;
+
+        /// The output to put in place of the item that failed at `span`, if any.
+        fn placeholder(&self, span: S) -> Option<O>;
+}
+
+/// End the sequence before an item that failed. This is the default.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Stop;
+
+impl<S, O> OnItemError<S, O> for Stop {
+        const SKIP: bool = false;
+
+        fn placeholder(&self, _span: S) -> Option<O> {
+                None
+        }
+}
+
+/// Skip items that failed. See [`Repeated::recover_items`].
+#[derive(Copy, Clone, Debug, Default)]
+pub struct SkipItems;
+
+impl<S, O> OnItemError<S, O> for SkipItems {
+        const SKIP: bool = true;
+
+        fn placeholder(&self, _span: S) -> Option<O> {
+                None
+        }
 }
 
-fn repeated_impl<I: InputType, O, E: ParserExtras<I>, P: Parser<I, O, E>, M: Mode>(
-        this: &Repeated<P, O>,
+/// Skip items that failed, producing a placeholder in their place. See [`Repeated::recover_items_with`].
+#[derive(Copy, Clone, Debug)]
+pub struct Placeholder<F>(F);
+
+impl<S, O, F: Fn(S) -> O> OnItemError<S, O> for Placeholder<F> {
+        const SKIP: bool = true;
+
+        fn placeholder(&self, span: S) -> Option<O> {
+                Some((self.0)(span))
+        }
+}
+
+/// Handle the failure of an item of a sequence that started at `before`.
+///
+/// If the sequence `skips` failed items and this one consumed input, `error` is recorded as a secondary error
+/// and the input is left where `sync` (the next item or delimiter) matches, returning the placeholder for the item, if any.
+/// Otherwise, the input is rewound to `before` and `error` is returned, ending the sequence.
+fn recover_item<
+        I: InputType,
+        O,
+        E: ParserExtras<I>,
+        R: OnItemError<I::Span, O>,
+        S: Parser<I, SO, E>,
+        SO,
+>(
+        on_error: &R,
+        skips: bool,
+        input: &mut Input<I, E>,
+        before: Marker<I>,
+        error: E::Error,
+        sync: &S,
+) -> Result<Option<O>, E::Error> {
+        #[cfg(feature = "error-recovery")]
+        if skips && recovery::failed_after_consuming(input, &before) && input.attempt_recovery() {
+                input.rewind(before);
+                if let Some(at) = recovery::skip_to(input, sync) {
+                        let span = input.input.span(before.offset..at.offset);
+                        input.errors.emit(span, error);
+                        return Ok(on_error.placeholder(input.input.span(before.offset..at.offset)));
+                }
+        }
+        #[cfg(not(feature = "error-recovery"))]
+        let _ = (on_error, skips, sync);
+
+        // don't leave the input wherever the failed item stopped
+        input.rewind(before);
+        Err(error)
+}
+
+fn repeated_impl<
+        I: InputType,
+        O,
+        E: ParserExtras<I>,
+        P: Parser<I, O, E>,
+        R: OnItemError<I::Span, O>,
+        M: Mode,
+>(
+        this: &Repeated<P, O, R>,
         input: &mut Input<I, E>,
         state: &mut usize,
 ) -> Result<Option<M::Output<O>>, E::Error> {
@@ -46,32 +214,51 @@ fn repeated_impl<I: InputType, O, E: ParserExtras<I>, P: Parser<I, O, E>, M: Mod
                 return Ok(None);
         }
 
-        let value = match M::invoke(&this.parser, input) {
-                Ok(ok) => ok,
-                Err(e) => {
-                        if *state >= this.at_least {
-                                return Ok(None);
-                        } else {
-                                return Err(e);
+        loop {
+                let before = input.save();
+                let error = match M::invoke(&this.parser, input) {
+                        Ok(value) => {
+                                *state += 1;
+                                return Ok(Some(value));
+                        }
+                        Err(error) => error,
+                };
+
+                let skips = Repeated::<P, O, R>::skips::<I, E>();
+                match recover_item(&this.on_error, skips, input, before, error, &this.parser) {
+                        Ok(Some(placeholder)) => {
+                                *state += 1;
+                                return Ok(Some(M::bind(|| placeholder)));
                         }
+                        // the failed item was skipped, parse the next one
+                        Ok(None) => {}
+                        Err(_) if *state >= this.at_least => return Ok(None),
+                        Err(error) => return Err(error),
                 }
-        };
-
-        *state += 1;
-
-        Ok(Some(value))
+        }
 }
 
-impl<I: InputType, O, E: ParserExtras<I>, P: Parser<I, O, E>> Parser<I, (), E> for Repeated<P, O> {
+impl<I: InputType, O, E: ParserExtras<I>, P: Parser<I, O, E>, R: OnItemError<I::Span, O>>
+        Parser<I, (), E> for Repeated<P, O, R>
+{
         fn parse_with(&self, input: &mut Input<I, E>) -> PResult<I, (), E> {
                 if self.at_most == !0 && self.at_least == 0 {
                         loop {
-                                #[cfg(debug_assertions)]
                                 let before = input.save();
 
-                                match self.parser.check_with(input) {
-                                        Ok(()) => {}
-                                        Err(_) => break Ok(()),
+                                if let Err(error) = self.parser.check_with(input) {
+                                        let skips = Self::skips::<I, E>();
+                                        let recovered = recover_item(
+                                                &self.on_error,
+                                                skips,
+                                                input,
+                                                before,
+                                                error,
+                                                &self.parser,
+                                        );
+                                        if recovered.is_err() {
+                                                break Ok(());
+                                        }
                                 }
 
                                 #[cfg(debug_assertions)]
@@ -104,12 +291,21 @@ impl<I: InputType, O, E: ParserExtras<I>, P: Parser<I, O, E>> Parser<I, (), E> f
         fn check_with(&self, input: &mut Input<I, E>) -> PResult<I, (), E> {
                 if self.at_most == !0 && self.at_least == 0 {
                         loop {
-                                #[cfg(debug_assertions)]
                                 let before = input.save();
 
-                                match self.parser.check_with(input) {
-                                        Ok(()) => {}
-                                        Err(_) => break Ok(()),
+                                if let Err(error) = self.parser.check_with(input) {
+                                        let skips = Self::skips::<I, E>();
+                                        let recovered = recover_item(
+                                                &self.on_error,
+                                                skips,
+                                                input,
+                                                before,
+                                                error,
+                                                &self.parser,
+                                        );
+                                        if recovered.is_err() {
+                                                break Ok(());
+                                        }
                                 }
 
                                 #[cfg(debug_assertions)]
@@ -140,7 +336,9 @@ impl<I: InputType, O, E: ParserExtras<I>, P: Parser<I, O, E>> Parser<I, (), E> f
         }
 }
 
-impl<I: InputType, O, E: ParserExtras<I>, P: Parser<I, O, E>> IterParser<I, E> for Repeated<P, O> {
+impl<I: InputType, O, E: ParserExtras<I>, P: Parser<I, O, E>, R: OnItemError<I::Span, O>>
+        IterParser<I, E> for Repeated<P, O, R>
+{
         type Item = O;
         type State = usize;
 
@@ -153,7 +351,7 @@ impl<I: InputType, O, E: ParserExtras<I>, P: Parser<I, O, E>> IterParser<I, E> f
                 input: &mut Input<I, E>,
                 state: &mut Self::State,
         ) -> Result<Option<Self::Item>, E::Error> {
-                repeated_impl::<_, _, _, _, Emit>(self, input, state)
+                repeated_impl::<_, _, _, _, _, Emit>(self, input, state)
         }
 
         fn check_next(
@@ -161,7 +359,7 @@ impl<I: InputType, O, E: ParserExtras<I>, P: Parser<I, O, E>> IterParser<I, E> f
                 input: &mut Input<I, E>,
                 state: &mut Self::State,
         ) -> Result<Option<()>, E::Error> {
-                repeated_impl::<_, _, _, _, Check>(self, input, state)
+                repeated_impl::<_, _, _, _, _, Check>(self, input, state)
         }
 }
 
@@ -302,25 +500,38 @@ impl<
                 T: Parser<I, O2, E>,
         > Parser<I, O, E> for Delimited<P, O1, C, T, O2>
 {
-        fn parse_with(&self, input: &mut Input<I, E>) -> PResult<I, O, E> {
+        fn go<M: Mode>(&self, input: &mut Input<I, E>) -> Result<M::Output<O>, E::Error> {
                 self.0.check_with(input)?;
-                let content = self.1.parse_with(input)?;
-                self.2.check_with(input)?;
+                let content = self.1.go::<M>(input)?;
+
+                let before = input.save();
+                match self.2.check_with(input) {
+                        Ok(()) => Ok(content),
+                        Err(error) => {
+                                // skip to the terminator that matches the preceding parser
+                                #[cfg(feature = "error-recovery")]
+                                if E::RECOVERY.delimited && input.attempt_recovery() {
+                                        input.rewind(before);
+                                        if let Some(at) = recovery::skip_to_terminator(
+                                                input, &self.0, &self.2,
+                                        ) {
+                                                let span = input.input.span(before.offset..at);
+                                                input.errors.emit(span, error);
+                                                return Ok(content);
+                                        }
+                                }
 
-                Ok(content)
+                                input.rewind(before);
+                                Err(error)
+                        }
+                }
         }
 
-        fn check_with(&self, input: &mut Input<I, E>) -> PResult<I, (), E> {
-                self.0.check_with(input)?;
-                self.1.check_with(input)?;
-                self.2.check_with(input)?;
-
-                Ok(())
-        }
+        go_extra!(O);
 }
 
 #[derive(Copy, Clone)]
-pub struct SeparatedBy<P, D, O, OD> {
+pub struct SeparatedBy<P, D, O, OD, R = Stop> {
         pub(crate) parser: P,
         pub(crate) delimiter: D,
         pub(crate) at_least: usize,
@@ -328,10 +539,11 @@ pub struct SeparatedBy<P, D, O, OD> {
         pub(crate) at_most: u64,
         pub(crate) allow_leading: bool,
         pub(crate) allow_trailing: bool,
+        pub(crate) on_error: R,
         pub(crate) phantom: PhantomData<(O, OD)>,
 }
 
-impl<P, D, O, OD> SeparatedBy<P, D, O, OD> {
+impl<P, D, O, OD, R> SeparatedBy<P, D, O, OD, R> {
         pub fn at_least(self, at_least: usize) -> Self {
                 Self { at_least, ..self }
         }
@@ -356,6 +568,74 @@ impl<P, D, O, OD> SeparatedBy<P, D, O, OD> {
                 self.allow_trailing = true;
                 self
         }
+
+        /// When an item fails after consuming input, record its error in the secondary errors and skip to
+        /// the next delimiter, instead of ending the list. Only the items that succeeded are produced.
+        ///
+        /// # Example
+        /// ```
+        /// # use aott::prelude::*;
+        /// let arg = (text::ascii::ident::<_, _, extra::Err<&str>>, just(':'), text::int(10));
+        /// let args = arg
+        ///     .map(|(name, _, value)| (name, value))
+        ///     .separated_by(just(','))
+        ///     .recover_items()
+        ///     .collect::<Vec<_>>();
+        ///
+        /// let result = args.parse_recovering("a:1,b:x,c:3");
+        /// assert_eq!(result.output(), Some(&vec![("a", "1"), ("c", "3")]));
+        /// assert_eq!(result.secondary()[0].span, 4..7);
+        /// ```
+        #[cfg(feature = "error-recovery")]
+        pub fn recover_items(self) -> SeparatedBy<P, D, O, OD, SkipItems> {
+                self.on_error(SkipItems)
+        }
+
+        /// Like [`SeparatedBy::recover_items`], but produce an item with `placeholder` from the skipped span
+        /// in place of each one that failed.
+        ///
+        /// # Example
+        /// ```
+        /// # use aott::prelude::*;
+        /// let arg = text::int::<_, _, extra::Err<&str>>(10).then_ignore(just(';')).map(Some);
+        /// let args = arg.separated_by(just(',')).recover_items_with(|_span| None).collect::<Vec<_>>();
+        ///
+        /// let result = args.parse_recovering("1;,2x,3;");
+        /// assert_eq!(result.output(), Some(&vec![Some("1"), None, Some("3")]));
+        /// ```
+        #[cfg(feature = "error-recovery")]
+        pub fn recover_items_with<F>(
+                self,
+                placeholder: F,
+        ) -> SeparatedBy<P, D, O, OD, Placeholder<F>> {
+                self.on_error(Placeholder(placeholder))
+        }
+
+        #[cfg(feature = "error-recovery")]
+        fn on_error<R2>(self, on_error: R2) -> SeparatedBy<P, D, O, OD, R2> {
+                SeparatedBy {
+                        parser: self.parser,
+                        delimiter: self.delimiter,
+                        at_least: self.at_least,
+                        at_most: self.at_most,
+                        allow_leading: self.allow_leading,
+                        allow_trailing: self.allow_trailing,
+                        on_error,
+                        phantom: PhantomData,
+                }
+        }
+
+        /// Whether failed items are skipped, because of [`SeparatedBy::recover_items`] or the recovery config of `E`.
+        fn skips<I: InputType, E: ParserExtras<I>>() -> bool
+        where
+                R: OnItemError<I::Span, O>,
+        {
+                #[cfg(feature = "error-recovery")]
+                if E::RECOVERY.separated_by {
+                        return true;
+                }
+                R::SKIP
+        }
 }
 
 fn sep_impl<
@@ -365,9 +645,10 @@ fn sep_impl<
         E: ParserExtras<I>,
         P: Parser<I, O, E>,
         D: Parser<I, OD, E>,
+        R: OnItemError<I::Span, O>,
         M: Mode,
 >(
-        this: &SeparatedBy<P, D, O, OD>,
+        this: &SeparatedBy<P, D, O, OD, R>,
         input: &mut Input<I, E>,
         state: &mut usize,
 ) -> Result<Option<M::Output<O>>, E::Error> {
@@ -382,7 +663,16 @@ fn sep_impl<
         }
 
         if *state > 0 {
-                this.delimiter.check_with(input)?;
+                // no delimiter means the end of the list
+                let before_delimiter = input.save();
+                if let Err(error) = this.delimiter.check_with(input) {
+                        input.rewind(before_delimiter);
+                        return if *state >= this.at_least {
+                                Ok(None)
+                        } else {
+                                Err(error)
+                        };
+                }
         } else if this.allow_leading && *state == 0 {
                 let before_delimiter = input.save();
                 if let Err(_) = this.delimiter.check_with(input) {
@@ -390,24 +680,39 @@ fn sep_impl<
                 }
         }
 
-        let value = match M::invoke(&this.parser, input) {
-                Ok(ok) => ok,
-                Err(e) => {
-                        if *state >= this.at_least {
-                                return Ok(None);
-                        } else {
-                                return Err(e);
+        loop {
+                let before = input.save();
+                let error = match M::invoke(&this.parser, input) {
+                        Ok(value) => {
+                                *state += 1;
+                                return Ok(Some(value));
                         }
+                        Err(error) => error,
+                };
+
+                let skips = SeparatedBy::<P, D, O, OD, R>::skips::<I, E>();
+                match recover_item(&this.on_error, skips, input, before, error, &this.delimiter) {
+                        Ok(Some(placeholder)) => {
+                                *state += 1;
+                                return Ok(Some(M::bind(|| placeholder)));
+                        }
+                        // the failed item was skipped up to the delimiter, parse the item after it
+                        Ok(None) => this.delimiter.check_with(input)?,
+                        Err(_) if *state >= this.at_least => return Ok(None),
+                        Err(error) => return Err(error),
                 }
-        };
-
-        *state += 1;
-
-        Ok(Some(value))
+        }
 }
 
-impl<I: InputType, O, OD, E: ParserExtras<I>, P: Parser<I, O, E>, D: Parser<I, OD, E>>
-        IterParser<I, E> for SeparatedBy<P, D, O, OD>
+impl<
+                I: InputType,
+                O,
+                OD,
+                E: ParserExtras<I>,
+                P: Parser<I, O, E>,
+                D: Parser<I, OD, E>,
+                R: OnItemError<I::Span, O>,
+        > IterParser<I, E> for SeparatedBy<P, D, O, OD, R>
 {
         type Item = O;
         type State = usize;
@@ -421,7 +726,7 @@ impl<I: InputType, O, OD, E: ParserExtras<I>, P: Parser<I, O, E>, D: Parser<I, O
                 input: &mut Input<I, E>,
                 state: &mut Self::State,
         ) -> Result<Option<Self::Item>, E::Error> {
-                sep_impl::<_, _, _, _, _, _, Emit>(self, input, state)
+                sep_impl::<_, _, _, _, _, _, _, Emit>(self, input, state)
         }
 
         fn check_next(
@@ -429,6 +734,34 @@ impl<I: InputType, O, OD, E: ParserExtras<I>, P: Parser<I, O, E>, D: Parser<I, O
                 input: &mut Input<I, E>,
                 state: &mut Self::State,
         ) -> Result<Option<()>, E::Error> {
-                sep_impl::<_, _, _, _, _, _, Check>(self, input, state)
+                sep_impl::<_, _, _, _, _, _, _, Check>(self, input, state)
+        }
+}
+
+#[cfg(test)]
+mod test {
+        use crate::prelude::*;
+
+        // a failed item must not leave the input where it stopped: `just("ab")` reads the `a` of `ac`
+        // before failing, which used to be consumed as well
+        #[test]
+        fn repeated_rewinds_failed_item() {
+                let items = just::<_, _, extra::Err<&str>>("ab").repeated().slice();
+                assert_eq!(items.parse_prefix("ababac"), Ok(("abab", "ac")));
+
+                let items = just::<_, _, extra::Err<&str>>("ab")
+                        .repeated()
+                        .at_least(1)
+                        .collect::<Vec<_>>();
+                assert_eq!(items.parse_prefix("ababac"), Ok((vec!["ab", "ab"], "ac")));
+        }
+
+        #[test]
+        fn repeated_then_rest() {
+                let parser = just::<_, _, extra::Err<&str>>("ab")
+                        .repeated()
+                        .then(just("ac"))
+                        .slice();
+                assert_eq!(parser.parse("ababac"), Ok("ababac"));
         }
 }
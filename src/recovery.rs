@@ -2,18 +2,181 @@
 //!
 //! The thing you're gonna be using the most - [`Strategy`]-ies, they define how you try to recover from errors.
 //! You could use a parser to recover - that's a strategy - [`via_parser`];
-//! you could do nothing - that's also a strategy - [`noop`].
+//! you could do nothing - that's also a strategy - [`noop`];
+//! you could skip tokens one by one, trying again after each one - [`skip_then_retry_until`];
+//! you could skip a whole bracketed construct, nested brackets included - [`nested_delimiters`];
+//! you could skip up to a synchronisation point, like a `;`, and put a placeholder in place of what failed - [`skip_until`].
+//!
+//! Sequences can also recover on their own, without wrapping every element in [`Parser::recover_with`]:
+//! [`Repeated`], [`SeparatedBy`] and [`Delimited`] know what can be parsed next (the next item, delimiter or terminator),
+//! so they can skip to it when an element fails. Which of them do that is configured with [`ParserExtras::RECOVERY`],
+//! see [`Config`]. A single [`Repeated`] or [`SeparatedBy`] can also opt in on its own with `recover_items`,
+//! or `recover_items_with` to put placeholders in place of the items that failed.
+//!
+//! [`Repeated`]: crate::primitive::Repeated
+//! [`SeparatedBy`]: crate::primitive::SeparatedBy
+//! [`Delimited`]: crate::primitive::Delimited
 //!
 //! Currently, there aren't many useful strategies, no, not even a lot of strategies at all; and this whole module is at its infancy and things could change, and they *would* change,
 //! and if you use this before it's stable, you take the responsibility for using it, and take on the burden of adapting your code to new changes when you update.
 //! It's not as unstable as other, smaller features, but it isn't stable, tested and ready to use either.
+use std::marker::PhantomData;
+
 use crate::{
+        error::{Error, Suggestion},
         go_extra,
-        input::{Input, InputType},
+        input::{Edit, Input, InputType, Marker},
         parser::Mode,
         prelude::{Parser, ParserExtras},
+        primitive::MemoTables,
 };
 
+/// How parsers recover from errors, configured with [`ParserExtras::RECOVERY`]:
+/// which sequence combinators automatically resynchronise when one of their elements fails,
+/// and how much recovery is allowed before giving up on the input.
+///
+/// The error of the failed element is emitted as a secondary error, located at the skipped span.
+///
+/// # Example
+/// ```
+/// # use aott::prelude::*;
+/// // `extra::Recover` enables all of the automatic recovery
+/// let statement = (text::ascii::ident::<_, _, extra::Recover<&str>>, just('='), text::int(10), just(';'))
+///     .map(|(name, _, value, _)| (name, value));
+/// let block = statement.repeated().collect::<Vec<_>>().delimited_by(just('{'), just('}'));
+///
+/// let result = block.parse_recovering("{a=1;b=x;c=3;d}");
+/// assert_eq!(result.output(), Some(&vec![("a", "1"), ("c", "3")]));
+/// // `b=x;` was skipped up to the next statement, `d` up to the closing brace
+/// assert_eq!(result.secondary()[0].span, 5..9);
+/// assert_eq!(result.secondary()[1].span, 13..14);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Config {
+        /// When an item of [`Repeated`](crate::primitive::Repeated) fails after consuming input,
+        /// skip tokens until an item can be parsed again. If none can, the repetition stops before the failed item.
+        pub repeated: bool,
+        /// When an item of [`SeparatedBy`](crate::primitive::SeparatedBy) fails after consuming input,
+        /// skip tokens until the next delimiter. If there is none, the list ends before the failed item.
+        pub separated_by: bool,
+        /// When the terminator of [`Delimited`](crate::primitive::Delimited) doesn't follow its content,
+        /// skip tokens up to the matching terminator, counting nested pairs of the preceding and terminating parsers.
+        pub delimited: bool,
+        /// How many times recovery may be attempted during a single parse, either by a [`Strategy`]
+        /// or by the sequence combinators above. Once reached, errors are returned as if there was no recovery.
+        ///
+        /// Attempts are counted even if they fail or are later backtracked out of,
+        /// so this also bounds the work spent on recovering.
+        pub max_attempts: Option<usize>,
+        /// How many secondary errors (of [`Severity::Error`](crate::error::Severity::Error)) may be emitted
+        /// before recovery stops, so that badly broken input fails at the next error instead of flooding the user
+        /// with every error after it.
+        ///
+        /// # Example
+        /// ```
+        /// # use aott::prelude::*;
+        /// use aott::recovery::Config;
+        ///
+        /// struct Limited;
+        /// impl<'a> ParserExtras<&'a str> for Limited {
+        ///     type Error = extra::Simple<&'a str>;
+        ///     type Context = ();
+        ///     const RECOVERY: Config = Config { max_errors: Some(2), ..Config::NONE };
+        /// }
+        ///
+        /// let expr = text::int::<_, _, Limited>(10).recover_with(skip_until(just(';'), |_| "?"));
+        /// let statements = expr.then_ignore(just(';')).repeated().collect::<Vec<_>>();
+        ///
+        /// assert_eq!(statements.parse_recovering("1;x;2;").output(), Some(&vec!["1", "?", "2"]));
+        /// // `x` and `y` are recovered from, but `z` isn't
+        /// let result = statements.then_ignore(end).parse_recovering("1;x;y;z;5;");
+        /// assert_eq!(result.output(), None);
+        /// assert_eq!(result.secondary().len(), 2);
+        /// ```
+        pub max_errors: Option<usize>,
+}
+
+impl Config {
+        /// No automatic recovery, the default.
+        pub const NONE: Self = Self {
+                repeated: false,
+                separated_by: false,
+                delimited: false,
+                max_attempts: None,
+                max_errors: None,
+        };
+
+        /// Automatic recovery in all of the sequence combinators.
+        pub const ALL: Self = Self {
+                repeated: true,
+                separated_by: true,
+                delimited: true,
+                max_attempts: None,
+                max_errors: None,
+        };
+}
+
+/// Whether the element that started at `before` failed after consuming input,
+/// as opposed to failing right away because the sequence has ended.
+pub(crate) fn failed_after_consuming<I: InputType, E: ParserExtras<I>>(
+        input: &Input<I, E>,
+        before: &Marker<I>,
+) -> bool {
+        input.last_read > before.offset
+}
+
+/// Skip at least one token, then keep skipping tokens one at a time until `parser` would succeed.
+/// Returns a marker right before where it would, leaving the input there,
+/// or `None` if the end of input was reached first.
+pub(crate) fn skip_to<I: InputType, O, E: ParserExtras<I>, P: Parser<I, O, E>>(
+        input: &mut Input<I, E>,
+        parser: &P,
+) -> Option<Marker<I>> {
+        loop {
+                input.next_or_none()?;
+                let before = input.save();
+                let matched = parser.check_with(input).is_ok();
+                input.rewind(before);
+                if matched {
+                        return Some(before);
+                }
+        }
+}
+
+/// Skip tokens up to the terminator matching an already consumed `open`, counting nested pairs of `open` and `close`,
+/// and consume it. Returns the offset the terminator started at, or `None` if the end of input was reached first.
+pub(crate) fn skip_to_terminator<
+        I: InputType,
+        E: ParserExtras<I>,
+        A: Parser<I, AO, E>,
+        AO,
+        B: Parser<I, BO, E>,
+        BO,
+>(
+        input: &mut Input<I, E>,
+        open: &A,
+        close: &B,
+) -> Option<I::Offset> {
+        let mut depth = 0usize;
+        loop {
+                let before = input.save();
+                if close.check_with(input).is_ok() {
+                        match depth.checked_sub(1) {
+                                Some(outer) => depth = outer,
+                                None => return Some(before.offset),
+                        }
+                        continue;
+                }
+                input.rewind(before);
+                if open.check_with(input).is_ok() {
+                        depth += 1;
+                        continue;
+                }
+                input.rewind(before);
+                input.next_or_none()?;
+        }
+}
+
 pub trait Strategy<I: InputType, O, E: ParserExtras<I>> {
         /// Attempt to recover from a parsing failure.
         ///
@@ -26,6 +189,54 @@ pub trait Strategy<I: InputType, O, E: ParserExtras<I>> {
                 parser: &P,
                 error: E::Error,
         ) -> Result<M::Output<O>, E::Error>;
+
+        /// Try this strategy, and if it fails to recover, try `other` instead.
+        ///
+        /// # Example
+        /// ```
+        /// # use aott::prelude::*;
+        /// let atom = text::int::<_, _, extra::Err<&str>>(10).map(Some);
+        /// // skip a whole parenthesized expression, else skip up to the end of the statement
+        /// let expr = atom.recover_with(
+        ///     nested_delimiters([('(', ')')], |_| None)
+        ///         .or(skip_until(just(';'), |_| None))
+        ///         .or(noop()),
+        /// );
+        /// let statements = expr.then_ignore(just(';')).repeated().collect::<Vec<_>>();
+        ///
+        /// let result = statements.parse_recovering("1;(2;3);x y;4;");
+        /// assert_eq!(result.output(), Some(&vec![Some("1"), None, None, Some("4")]));
+        /// assert_eq!(result.secondary()[0].span, 2..7);
+        /// assert_eq!(result.secondary()[1].span, 8..11);
+        /// ```
+        fn or<S: Strategy<I, O, E>>(self, other: S) -> Or<Self, S>
+        where
+                Self: Sized,
+        {
+                Or(self, other)
+        }
+}
+
+/// See [`Strategy::or`].
+#[derive(Copy, Clone)]
+pub struct Or<A, B>(A, B);
+
+impl<I: InputType, O, E: ParserExtras<I>, A: Strategy<I, O, E>, B: Strategy<I, O, E>>
+        Strategy<I, O, E> for Or<A, B>
+{
+        fn recover<M: Mode, P: Parser<I, O, E>>(
+                &self,
+                input: &mut Input<I, E>,
+                parser: &P,
+                error: E::Error,
+        ) -> Result<M::Output<O>, E::Error> {
+                let before = input.save();
+                self.0.recover::<M, P>(input, parser, error)
+                        .or_else(|error| {
+                                input.rewind(before);
+                                self.1.recover::<M, P>(input, parser, error)
+                        })
+        }
 }
 
 #[derive(Copy, Clone)]
@@ -40,9 +251,10 @@ impl<I: InputType, O, E: ParserExtras<I>, A: Parser<I, O, E>> Strategy<I, O, E>
         ) -> Result<M::Output<O>, E::Error> {
                 // emit the error as a secondary if the parser succeeds at recovering,
                 // else return the initial error
+                let before = input.offset;
                 match self.0.go::<M>(input) {
                         Ok(out) => {
-                                input.errors.emit(input.offset, error);
+                                input.errors.emit(input.span_since(before), error);
                                 Ok(out)
                         }
                         Err(_) => Err(error),
@@ -74,6 +286,361 @@ pub fn noop() -> NoOp {
         NoOp
 }
 
+#[derive(Copy, Clone)]
+pub struct SkipThenRetryUntil<U, UO>(U, PhantomData<UO>);
+
+impl<I: InputType, O, E: ParserExtras<I>, U: Parser<I, UO, E>, UO> Strategy<I, O, E>
+        for SkipThenRetryUntil<U, UO>
+{
+        fn recover<M: Mode, P: Parser<I, O, E>>(
+                &self,
+                input: &mut Input<I, E>,
+                parser: &P,
+                error: E::Error,
+        ) -> Result<M::Output<O>, E::Error> {
+                let start = input.offset;
+                loop {
+                        // give up before the terminator, or at the end of input
+                        let before = input.save();
+                        let terminated = self.0.check_with(input).is_ok();
+                        input.rewind(before);
+                        if terminated || input.next_or_none().is_none() {
+                                return Err(error);
+                        }
+
+                        let skipped = input.span_since(start);
+                        let before = input.save();
+                        match parser.go::<M>(input) {
+                                Ok(out) => {
+                                        input.errors.emit(skipped, error);
+                                        return Ok(out);
+                                }
+                                Err(_) => input.rewind(before),
+                        }
+                }
+        }
+}
+
+/// Skip a single token, then try the failed parser again, repeating until it succeeds.
+///
+/// Gives up (returning the original error) when `until` matches (without consuming it), or at the end of input.
+/// On success, the original error is emitted as a secondary error, located at the skipped tokens.
+///
+/// # Example
+/// ```
+/// # use aott::prelude::*;
+/// let statement = (text::ascii::ident::<_, _, extra::Err<&str>>, just(';'))
+///     .map(|(name, _)| name)
+///     .recover_with(skip_then_retry_until(just('}')));
+/// let block = statement.repeated().collect::<Vec<_>>().then_ignore(just('}'));
+///
+/// let result = block.parse_recovering("a;12b;c;}");
+/// assert_eq!(result.output(), Some(&vec!["a", "b", "c"]));
+/// assert_eq!(result.secondary()[0].span, 2..4);
+/// ```
+pub fn skip_then_retry_until<U, UO>(until: U) -> SkipThenRetryUntil<U, UO> {
+        SkipThenRetryUntil(until, PhantomData)
+}
+
+#[derive(Copy, Clone)]
+pub struct NestedDelimiters<T, F, const N: usize> {
+        pairs: [(T, T); N],
+        fallback: F,
+}
+
+impl<I: InputType, O, E: ParserExtras<I>, F: Fn(I::Span) -> O, const N: usize> Strategy<I, O, E>
+        for NestedDelimiters<I::Token, F, N>
+where
+        I::Token: PartialEq,
+{
+        fn recover<M: Mode, P: Parser<I, O, E>>(
+                &self,
+                input: &mut Input<I, E>,
+                _parser: &P,
+                error: E::Error,
+        ) -> Result<M::Output<O>, E::Error> {
+                let start = input.offset;
+                let closer = |token: &I::Token| {
+                        self.pairs
+                                .iter()
+                                .find(|(open, _)| open == token)
+                                .map(|(_, close)| close)
+                };
+
+                // the failed parser must have started at an opening delimiter
+                let Some(first) = input.next_or_none().as_ref().and_then(closer) else {
+                        return Err(error);
+                };
+                let mut expected = vec![first];
+                while let Some(&close) = expected.last() {
+                        // unbalanced input: the end was reached with delimiters left open
+                        let Some(token) = input.next_or_none() else {
+                                return Err(error);
+                        };
+                        if let Some(inner) = closer(&token) {
+                                expected.push(inner);
+                        } else if token == *close {
+                                expected.pop();
+                        } else if self.pairs.iter().any(|(_, close)| *close == token) {
+                                // a closing delimiter that doesn't match the innermost opening one
+                                return Err(error);
+                        }
+                }
+
+                let span = input.span_since(start);
+                input.errors.emit(input.span_since(start), error);
+                Ok(M::bind(|| (self.fallback)(span)))
+        }
+}
+
+/// Skip a whole delimited construct, like `( … )`, `[ … ]` or `{ … }`, counting nested pairs of `pairs`,
+/// and produce an output with `fallback` from the skipped span.
+///
+/// The failed parser must have started at an opening delimiter, and the construct is skipped up to and including
+/// its matching closing delimiter.
+/// Gives up (returning the original error) if a closing delimiter does not match the innermost open one,
+/// or the end of input is reached with delimiters left open.
+/// On success, the original error is emitted as a secondary error, located at the skipped span.
+///
+/// # Example
+/// ```
+/// # use aott::prelude::*;
+/// #[derive(Debug, PartialEq)]
+/// enum Expr {
+///     Parens(&'static str),
+///     Error(std::ops::Range<usize>),
+/// }
+///
+/// let parens = (just::<_, _, extra::Err<&str>>('('), text::ascii::ident, just(')'))
+///     .map(|(_, name, _)| Expr::Parens(name))
+///     .recover_with(nested_delimiters([('(', ')'), ('[', ']'), ('{', '}')], Expr::Error));
+///
+/// assert_eq!(parens.parse_recovering("(x)").output(), Some(&Expr::Parens("x")));
+/// let result = parens.parse_recovering("(f(x)[y])");
+/// assert_eq!(result.output(), Some(&Expr::Error(0..9)));
+/// assert_eq!(result.secondary()[0].span, 0..9);
+///
+/// // mismatched delimiters can't be recovered from
+/// assert!(parens.parse_recovering("(f(x]]").output().is_none());
+/// ```
+pub fn nested_delimiters<T, F, const N: usize>(
+        pairs: [(T, T); N],
+        fallback: F,
+) -> NestedDelimiters<T, F, N> {
+        NestedDelimiters { pairs, fallback }
+}
+
+#[derive(Copy, Clone)]
+pub struct SkipUntil<U, UO, F> {
+        until: U,
+        fallback: F,
+        inclusive: bool,
+        phantom: PhantomData<UO>,
+}
+
+impl<U, UO, F> SkipUntil<U, UO, F> {
+        /// Consume the synchroniser too, instead of leaving it for the next parser.
+        #[must_use]
+        pub fn inclusive(self) -> Self {
+                Self {
+                        inclusive: true,
+                        ..self
+                }
+        }
+}
+
+impl<I: InputType, O, E: ParserExtras<I>, U: Parser<I, UO, E>, UO, F: Fn(I::Span) -> O>
+        Strategy<I, O, E> for SkipUntil<U, UO, F>
+{
+        fn recover<M: Mode, P: Parser<I, O, E>>(
+                &self,
+                input: &mut Input<I, E>,
+                _parser: &P,
+                error: E::Error,
+        ) -> Result<M::Output<O>, E::Error> {
+                let start = input.offset;
+                loop {
+                        let before = input.save();
+                        if self.until.check_with(input).is_ok() {
+                                let end = before.offset;
+                                if !self.inclusive {
+                                        input.rewind(before);
+                                }
+                                // recovering without consuming anything would never end inside of `repeated`
+                                if input.offset == start {
+                                        return Err(error);
+                                }
+                                input.errors.emit(input.input.span(start..end), error);
+                                let skipped = input.input.span(start..end);
+                                return Ok(M::bind(|| (self.fallback)(skipped)));
+                        }
+                        input.rewind(before);
+                        if input.next_or_none().is_none() {
+                                return Err(error);
+                        }
+                }
+        }
+}
+
+/// Skip tokens until `until` (the synchroniser) matches, then produce an output with `fallback` from the skipped span.
+///
+/// The synchroniser is left for the next parser, unless [`SkipUntil::inclusive`] is used.
+/// Gives up (returning the original error) if the end of input is reached first,
+/// or if nothing would be consumed, because the synchroniser is right where the parser failed.
+/// On success, the original error is emitted as a secondary error, located at the skipped span.
+///
+/// # Example
+/// ```
+/// # use aott::prelude::*;
+/// #[derive(Debug, PartialEq)]
+/// enum Expr {
+///     Number(&'static str),
+///     Error(std::ops::Range<usize>),
+/// }
+///
+/// let expr = text::int::<_, _, extra::Err<&str>>(10)
+///     .map(Expr::Number)
+///     .recover_with(skip_until(just(';'), Expr::Error));
+/// let statements = expr.then_ignore(just(';')).repeated().collect::<Vec<_>>();
+///
+/// let result = statements.parse_recovering("1;x+y;3;");
+/// assert_eq!(
+///     result.output(),
+///     Some(&vec![Expr::Number("1"), Expr::Error(2..5), Expr::Number("3")])
+/// );
+/// assert_eq!(result.secondary()[0].span, 2..5);
+/// ```
+pub fn skip_until<U, UO, F>(until: U, fallback: F) -> SkipUntil<U, UO, F> {
+        SkipUntil {
+                until,
+                fallback,
+                inclusive: false,
+                phantom: PhantomData,
+        }
+}
+
+#[derive(Default, Copy, Clone)]
+pub struct Repair;
+
+impl<I: InputType, O, E: ParserExtras<I>> Strategy<I, O, E> for Repair
+where
+        I::Token: Clone,
+{
+        fn recover<M: Mode, P: Parser<I, O, E>>(
+                &self,
+                input: &mut Input<I, E>,
+                parser: &P,
+                error: E::Error,
+        ) -> Result<M::Output<O>, E::Error> {
+                // an edit is already being tried out, don't try another one on top of it
+                if input.edit.is_some() {
+                        return Err(error);
+                }
+
+                let at = input.last_read;
+                // SAFETY: `last_read` was generated by a previous call to `Input::next`
+                let (after, found) = unsafe { input.input.next(at) };
+                let mut suggestions: Vec<_> = error
+                        .expected_tokens()
+                        .into_iter()
+                        .map(|token| Suggestion::Insert {
+                                span: at..at,
+                                token: token.clone(),
+                        })
+                        .collect();
+                if let Some(token) = found {
+                        suggestions.push(Suggestion::Delete {
+                                span: at..after,
+                                token,
+                        });
+                }
+
+                let before = input.save();
+                // memos are of the input without the edit, so the trials get their own
+                let memos = std::mem::take(&mut input.memos);
+                let mut repaired = None;
+                for suggestion in suggestions {
+                        let insert = match &suggestion {
+                                Suggestion::Insert { token, .. } => Some(token.clone()),
+                                // only insertions and deletions are tried
+                                _ => None,
+                        };
+                        input.edit = Some(Edit {
+                                at,
+                                insert,
+                                clone: Clone::clone,
+                                applied: false,
+                        });
+                        input.skip_deletion();
+                        input.memos = MemoTables::default();
+                        let result = parser.go::<M>(input);
+                        let applied = input.edit.take().is_some_and(|edit| edit.applied);
+                        match result {
+                                Ok(out) if applied => {
+                                        repaired = Some((out, suggestion));
+                                        break;
+                                }
+                                _ => input.rewind(before),
+                        }
+                }
+                input.memos = memos;
+
+                let Some((out, suggestion)) = repaired else {
+                        return Err(error);
+                };
+                let span = input.input.span(suggestion.span().clone());
+                let suggestion = suggestion.map_span(|span| input.input.span(span));
+                input.errors.emit(span, error.suggest(suggestion));
+                Ok(out)
+        }
+}
+
+/// Try to repair the input where the parser failed, by pretending that a single token was inserted there,
+/// or that the offending token was deleted, and parsing again.
+///
+/// The tokens tried for insertion are the ones the error expected (see [`Error::expected_tokens`]),
+/// in order, and deleting the offending token is tried last.
+/// The first edit with which the failed parser succeeds is kept, and the original error is emitted as
+/// a secondary error with the edit attached as a [`Suggestion`] (see [`Error::suggest`]).
+/// Gives up (returning the original error) if none of the edits work.
+///
+/// Only a single edit is tried at a time: a parser that is being repaired isn't repaired again.
+/// This uses where the parser failed, so if it is combined with other strategies with [`Strategy::or`],
+/// it should come first.
+///
+/// # Example
+/// ```
+/// # use aott::prelude::*;
+/// use aott::{error::Suggestion, extra::Rich, report::Report};
+/// let ident = text::ascii::ident::<_, _, extra::Err<&str, Rich<&str>>>;
+/// let args = ident.separated_by(just(',')).collect::<Vec<_>>();
+/// let call = (ident, just('('), args, just(')'), just(';'))
+///     .map(|(name, _, args, _, _)| (name, args))
+///     .recover_with(repair());
+///
+/// // a missing `)` is inserted
+/// let result = call.parse_recovering("f(a,b;");
+/// assert_eq!(result.output(), Some(&("f", vec!["a", "b"])));
+/// assert_eq!(
+///     result.secondary()[0].err.suggestions(),
+///     &[Suggestion::Insert { span: 5..5, token: ')' }]
+/// );
+/// let report = Report::new("f(a,b;").render(&result.secondary()[0].err);
+/// assert!(report.ends_with("= help: insert ')'\n"));
+///
+/// // a duplicated `,` is deleted
+/// let result = call.parse_recovering("f(a,,b);");
+/// assert_eq!(result.output(), Some(&("f", vec!["a", "b"])));
+/// assert_eq!(
+///     result.secondary()[0].err.suggestions(),
+///     &[Suggestion::Delete { span: 4..5, token: ',' }]
+/// );
+/// ```
+#[must_use]
+pub fn repair() -> Repair {
+        Repair
+}
+
 #[derive(Copy, Clone)]
 pub struct RecoverWith<A, S> {
         pub(crate) parser: A,
@@ -90,6 +657,9 @@ impl<I: InputType, O, E: ParserExtras<I>, A: Parser<I, O, E>, S: Strategy<I, O,
                         Ok(out) => Ok(out),
                         Err(error) => {
                                 input.rewind(before);
+                                if !input.attempt_recovery() {
+                                        return Err(error);
+                                }
 
                                 match self.strategy.recover::<M, A>(input, &self.parser, error) {
                                         Ok(out) => Ok(out),
@@ -104,3 +674,124 @@ impl<I: InputType, O, E: ParserExtras<I>, A: Parser<I, O, E>, S: Strategy<I, O,
 
         go_extra!(O);
 }
+
+#[cfg(test)]
+mod test {
+        use crate::{extra::Simple, prelude::*};
+
+        use super::Config;
+
+        struct MaxErrors;
+        impl<'a> ParserExtras<&'a str> for MaxErrors {
+                type Error = Simple<&'a str>;
+                type Context = ();
+                const RECOVERY: Config = Config {
+                        max_errors: Some(2),
+                        ..Config::NONE
+                };
+        }
+
+        struct MaxAttempts;
+        impl<'a> ParserExtras<&'a str> for MaxAttempts {
+                type Error = Simple<&'a str>;
+                type Context = ();
+                const RECOVERY: Config = Config {
+                        max_attempts: Some(2),
+                        ..Config::NONE
+                };
+        }
+
+        // `skip_until` used to succeed without consuming anything when the synchroniser was right where the
+        // parser failed, so `repeated` never ended
+        #[test]
+        fn skip_until_without_progress_fails() {
+                let int = || {
+                        text::int::<_, _, extra::Err<&str>>(10)
+                                .recover_with(skip_until(just(';'), |_| "?"))
+                };
+
+                let items = int().repeated().at_most(5).collect::<Vec<_>>();
+                let result = items.parse_recovering("1;2");
+                assert_eq!(result.output(), Some(&vec!["1"]));
+                assert!(result.secondary().is_empty());
+
+                let items = int().repeated().collect::<Vec<_>>();
+                assert_eq!(items.parse_prefix("1;2"), Ok((vec!["1"], ";2")));
+        }
+
+        #[test]
+        fn skip_until_inclusive_makes_progress() {
+                let int = text::int::<_, _, extra::Err<&str>>(10)
+                        .recover_with(skip_until(just(';'), |_| "?").inclusive());
+                let items = int.repeated().collect::<Vec<_>>();
+
+                let result = items.parse_recovering(";1;");
+                assert_eq!(result.output(), Some(&vec!["?", "1", "?"]));
+                assert_eq!(result.secondary().len(), 2);
+        }
+
+        #[test]
+        fn max_errors_stops_recovery() {
+                let expr = text::int::<_, _, MaxErrors>(10)
+                        .recover_with(skip_until(just(';'), |_| "?"));
+                let statements = expr.then_ignore(just(';')).repeated().collect::<Vec<_>>();
+
+                let result = statements.parse_recovering("1;x;y;z;5;");
+                assert_eq!(result.output(), Some(&vec!["1", "?", "?"]));
+                assert_eq!(result.secondary().len(), 2);
+                assert_eq!(result.secondary()[1].span, 4..5);
+        }
+
+        // with the synchroniser right where the item failed, nothing is recovered from, so no errors are used up
+        #[test]
+        fn max_errors_without_progress() {
+                let expr = text::int::<_, _, MaxErrors>(10)
+                        .recover_with(skip_until(just(';'), |_| "?"));
+                let items = expr.repeated().collect::<Vec<_>>();
+
+                let result = items.parse_recovering("1;2");
+                assert_eq!(result.output(), Some(&vec!["1"]));
+                assert!(result.secondary().is_empty());
+        }
+
+        // failed attempts count too
+        #[test]
+        fn max_attempts_stops_recovery() {
+                let expr = text::int::<_, _, MaxAttempts>(10)
+                        .recover_with(skip_until(just(';'), |_| "?"));
+                let statements = expr.then_ignore(just(';')).repeated().collect::<Vec<_>>();
+
+                let result = statements.parse_recovering("x;y;z;");
+                assert_eq!(result.output(), Some(&vec!["?", "?"]));
+                assert_eq!(result.secondary().len(), 2);
+
+                // the attempt at the end of input fails, but is counted
+                let result = statements.then(just('!')).parse_recovering("x;y");
+                assert_eq!(result.output(), None);
+                assert_eq!(result.secondary().len(), 1);
+        }
+
+        #[test]
+        fn strategy_or_falls_back() {
+                let expr = || {
+                        text::int::<_, _, extra::Err<&str>>(10)
+                                .map(Some)
+                                .recover_with(
+                                        skip_until(just(';'), |_| None)
+                                                .or(skip_until(just('.'), |_| Some("."))),
+                                )
+                };
+
+                let result = expr().then_ignore(just(';')).parse_recovering("x;");
+                assert_eq!(result.output(), Some(&None));
+
+                // there is no `;` to skip up to, so the second strategy is used
+                let result = expr().then_ignore(just('.')).parse_recovering("x.");
+                assert_eq!(result.output(), Some(&Some(".")));
+                assert_eq!(result.secondary()[0].span, 0..1);
+
+                let result = expr().parse_recovering("x");
+                assert_eq!(result.output(), None);
+                assert!(result.secondary().is_empty());
+        }
+}
@@ -0,0 +1,543 @@
+//! Rendering parse errors as human-readable diagnostics.
+//!
+//! Errors that implement [`Diagnostic`] (like [`Simple`](crate::extra::Simple) and [`Rich`](crate::extra::Rich))
+//! can be rendered with a [`Report`], which shows where the error happened in the source,
+//! marks the offending part of it and summarizes what was expected and found.
+//!
+//! # Example
+//! ```
+//! # use aott::prelude::*;
+//! use aott::{extra::Rich, report::Report};
+//! let parser = (just::<_, _, extra::Err<&str, Rich<&str>>>("let "), text::ascii::ident, just(';'));
+//! let source = "let x = 1;";
+//!
+//! let error = parser.parse(source).unwrap_err();
+//! assert_eq!(
+//!     Report::new(source).with_name("main.dsl").render(&error),
+//!     "\
+//! error: unexpected ' '
+//!  --> main.dsl:1:6
+//!   |
+//! 1 | let x = 1;
+//!   |      ^
+//!   = expected ';'
+//!   = found ' '
+//! "
+//! );
+//! ```
+use crate::input::{ColumnUnit, LineCol, LineIndex, Span};
+use std::fmt::{self, Display, Write};
+use std::ops::Range;
+
+/// An error that can be rendered by a [`Report`].
+pub trait Diagnostic {
+        /// The span type of this error. Its offsets must be byte offsets into the source.
+        type Span: Span<Offset = usize>;
+
+        /// The span this error occurred at. It is marked with carets in the report.
+        fn span(&self) -> &Self::Span;
+
+        /// A short, one-line description of this error, shown at the top of the report.
+        fn message(&self) -> String;
+
+        /// Everything that was expected to be found instead.
+        fn expected(&self) -> Vec<String> {
+                vec![]
+        }
+
+        /// What was found instead of what was expected, if known.
+        fn found(&self) -> Option<String> {
+                None
+        }
+
+        /// Additional notes shown at the bottom of the report.
+        fn notes(&self) -> Vec<String> {
+                vec![]
+        }
+
+        /// Suggested fixes, shown at the very bottom of the report.
+        fn help(&self) -> Vec<String> {
+                vec![]
+        }
+
+        /// Secondary spans related to this error, each with a label. They are underlined in the report.
+        fn labels(&self) -> Vec<(&Self::Span, String)> {
+                vec![]
+        }
+}
+
+/// Renders errors against the source they were produced from.
+///
+/// See the [module-level documentation](self) for an example.
+#[derive(Clone, Copy, Debug)]
+pub struct Report<'a> {
+        source: &'a str,
+        name: Option<&'a str>,
+        color: bool,
+}
+
+const RED: &str = "\x1b[1;31m";
+const BLUE: &str = "\x1b[1;34m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+struct Marker {
+        range: Range<usize>,
+        label: Option<String>,
+        primary: bool,
+}
+
+impl<'a> Report<'a> {
+        /// Creates a report for errors in `source`, without colours.
+        #[must_use]
+        pub fn new(source: &'a str) -> Self {
+                Self {
+                        source,
+                        name: None,
+                        color: false,
+                }
+        }
+
+        /// Sets the name of the source (usually a file name), which is shown before line and column numbers.
+        #[must_use]
+        pub fn with_name(self, name: &'a str) -> Self {
+                Self {
+                        name: Some(name),
+                        ..self
+                }
+        }
+
+        /// Sets whether the report should be coloured with ANSI escape codes.
+        #[must_use]
+        pub fn with_color(self, color: bool) -> Self {
+                Self { color, ..self }
+        }
+
+        /// Renders a single error.
+        pub fn render<D: Diagnostic>(&self, error: &D) -> String {
+                self.render_with(error, None::<(D::Span, &str)>)
+        }
+
+        /// Renders a single error, underlining additional `secondary` spans with their labels.
+        ///
+        /// # Example
+        /// ```
+        /// # use aott::prelude::*;
+        /// use aott::{extra::Rich, report::Report};
+        /// let list = (just::<_, _, extra::Err<&str, Rich<&str>>>("[\n"), just("  "), just(']')).in_context("list");
+        /// let source = "[\n  x";
+        ///
+        /// let error = list.parse(source).unwrap_err();
+        /// assert_eq!(
+        ///     Report::new(source).render_with(&error, [(0..1, "opened here")]),
+        ///     "\
+        /// error: unexpected 'x'
+        ///  --> 2:3
+        ///   |
+        /// 1 | [
+        ///   | -
+        ///   | - opened here
+        /// 2 |   x
+        ///   |   ^
+        ///   | -- while parsing list
+        ///   = expected ']'
+        ///   = found 'x'
+        /// "
+        /// );
+        /// ```
+        pub fn render_with<D: Diagnostic, S: Span<Offset = usize>, L: Display>(
+                &self,
+                error: &D,
+                secondary: impl IntoIterator<Item = (S, L)>,
+        ) -> String {
+                let mut output = String::new();
+                // writing to a `String` never fails
+                let _ = self.write(&mut output, error, secondary);
+                output
+        }
+
+        /// Renders several errors, one after another, separated by empty lines.
+        pub fn render_all<'e, D: Diagnostic + 'e>(
+                &self,
+                errors: impl IntoIterator<Item = &'e D>,
+        ) -> String {
+                let mut output = String::new();
+                for (i, error) in errors.into_iter().enumerate() {
+                        if i > 0 {
+                                output.push('\n');
+                        }
+                        output.push_str(&self.render(error));
+                }
+                output
+        }
+
+        /// Writes a single error to `w`, underlining additional `secondary` spans with their labels.
+        ///
+        /// # Errors
+        /// Returns an error if writing to `w` fails.
+        pub fn write<D: Diagnostic, S: Span<Offset = usize>, L: Display>(
+                &self,
+                w: &mut impl Write,
+                error: &D,
+                secondary: impl IntoIterator<Item = (S, L)>,
+        ) -> fmt::Result {
+                let mut markers = vec![Marker {
+                        range: error.span().range(),
+                        label: None,
+                        primary: true,
+                }];
+                markers.extend(error.labels().into_iter().map(|(span, label)| Marker {
+                        range: span.range(),
+                        label: Some(label),
+                        primary: false,
+                }));
+                markers.extend(secondary.into_iter().map(|(span, label)| Marker {
+                        range: span.range(),
+                        label: Some(label.to_string()),
+                        primary: false,
+                }));
+
+                let index = LineIndex::new(self.source);
+                let mut lines: Vec<usize> = markers
+                        .iter()
+                        .flat_map(|marker| {
+                                let (start, end) = self.lines_of(&index, &marker.range);
+                                start..=end
+                        })
+                        .collect();
+                lines.sort_unstable();
+                lines.dedup();
+
+                let width = (lines.last().copied().unwrap_or(0) + 1).to_string().len();
+                let pad = " ".repeat(width);
+
+                writeln!(
+                        w,
+                        "{}: {}",
+                        self.paint(RED, "error"),
+                        self.paint(BOLD, &error.message())
+                )?;
+                let LineCol { line, col } = self.position(&index, markers[0].range.start);
+                write!(w, "{pad}{} ", self.paint(BLUE, "-->"))?;
+                if let Some(name) = self.name {
+                        write!(w, "{name}:")?;
+                }
+                writeln!(w, "{}:{}", line + 1, col + 1)?;
+                writeln!(w, "{pad} {}", self.paint(BLUE, "|"))?;
+
+                for (i, &line) in lines.iter().enumerate() {
+                        if i > 0 && lines[i - 1] + 1 < line {
+                                writeln!(w, "{}", self.paint(BLUE, "..."))?;
+                        }
+                        let text = index.line(line).unwrap_or_default();
+                        writeln!(
+                                w,
+                                "{} {text}",
+                                self.paint(BLUE, &format!("{:>width$} |", line + 1))
+                        )?;
+                        for marker in &markers {
+                                self.write_marker(w, &index, &pad, line, text, marker)?;
+                        }
+                }
+
+                let expected = error.expected();
+                if !expected.is_empty() {
+                        write!(w, "{pad} {} expected ", self.paint(BLUE, "="))?;
+                        for (i, pattern) in expected.iter().enumerate() {
+                                match i {
+                                        0 => {}
+                                        _ if i + 1 == expected.len() => w.write_str(" or ")?,
+                                        _ => w.write_str(", ")?,
+                                }
+                                w.write_str(pattern)?;
+                        }
+                        writeln!(w)?;
+                }
+                if let Some(found) = error.found() {
+                        writeln!(w, "{pad} {} found {found}", self.paint(BLUE, "="))?;
+                }
+                for note in error.notes() {
+                        writeln!(
+                                w,
+                                "{pad} {} {}: {note}",
+                                self.paint(BLUE, "="),
+                                self.paint(BOLD, "note")
+                        )?;
+                }
+                for help in error.help() {
+                        writeln!(
+                                w,
+                                "{pad} {} {}: {help}",
+                                self.paint(BLUE, "="),
+                                self.paint(BOLD, "help")
+                        )?;
+                }
+                Ok(())
+        }
+
+        fn write_marker(
+                &self,
+                w: &mut impl Write,
+                index: &LineIndex<'_>,
+                pad: &str,
+                line: usize,
+                text: &str,
+                marker: &Marker,
+        ) -> fmt::Result {
+                let (start_line, end_line) = self.lines_of(index, &marker.range);
+                if !(start_line..=end_line).contains(&line) {
+                        return Ok(());
+                }
+
+                let from = if start_line == line {
+                        self.position(index, marker.range.start).col
+                } else {
+                        0
+                };
+                let to = if end_line == line {
+                        let end = self.position(index, marker.range.end);
+                        // the span may end after the line terminator
+                        if end.line == line {
+                                end.col
+                        } else {
+                                text.chars().count() + 1
+                        }
+                } else {
+                        text.chars().count()
+                };
+
+                // keep tabs, so that the markers line up with the source line
+                let indent: String = text
+                        .chars()
+                        .chain(std::iter::repeat(' '))
+                        .take(from)
+                        .map(|c| if c == '\t' { '\t' } else { ' ' })
+                        .collect();
+                let (symbol, color) = if marker.primary {
+                        ("^", RED)
+                } else {
+                        ("-", BLUE)
+                };
+                let underline = symbol.repeat(to.saturating_sub(from).max(1));
+
+                write!(
+                        w,
+                        "{pad} {} {indent}{}",
+                        self.paint(BLUE, "|"),
+                        self.paint(color, &underline)
+                )?;
+                match &marker.label {
+                        Some(label) if end_line == line => {
+                                writeln!(w, " {}", self.paint(color, label))
+                        }
+                        _ => writeln!(w),
+                }
+        }
+
+        fn paint(&self, color: &str, text: &str) -> String {
+                if self.color {
+                        format!("{color}{text}{RESET}")
+                } else {
+                        text.to_owned()
+                }
+        }
+
+        /// Clamps `offset` to the source, moving it back to the nearest char boundary.
+        fn clamp(&self, offset: usize) -> usize {
+                let mut offset = offset.min(self.source.len());
+                while !self.source.is_char_boundary(offset) {
+                        offset -= 1;
+                }
+                offset
+        }
+
+        /// Zero-based line and column (in chars) of `offset`.
+        fn position(&self, index: &LineIndex<'_>, offset: usize) -> LineCol {
+                index.line_col(self.clamp(offset), ColumnUnit::Char)
+                        .unwrap_or_default()
+        }
+
+        /// Zero-based first and last lines covered by `range`.
+        fn lines_of(&self, index: &LineIndex<'_>, range: &Range<usize>) -> (usize, usize) {
+                let (start, end) = (self.clamp(range.start), self.clamp(range.end));
+                // a non-empty span ending right after a newline doesn't cover the next line
+                let last = if end > start {
+                        self.clamp(end - 1)
+                } else {
+                        start
+                };
+                let start = self.position(index, start).line;
+                let end = self.position(index, last).line;
+                (start, end.max(start))
+        }
+}
+
+#[cfg(test)]
+mod test {
+        use super::*;
+        use crate::extra::Rich;
+
+        fn render(source: &str, span: Range<usize>) -> String {
+                Report::new(source).render(&Rich::<&str>::custom(span, "oops"))
+        }
+
+        #[test]
+        fn multi_line_span() {
+                assert_eq!(
+                        render("ab\ncd\nef", 1..7),
+                        "\
+error: oops
+ --> 1:2
+  |
+1 | ab
+  |  ^
+2 | cd
+  | ^^
+3 | ef
+  | ^
+"
+                );
+        }
+
+        #[test]
+        fn span_ending_after_a_newline() {
+                // the newline is marked, but the next line isn't shown
+                assert_eq!(
+                        render("ab\ncd", 0..3),
+                        "\
+error: oops
+ --> 1:1
+  |
+1 | ab
+  | ^^^
+"
+                );
+        }
+
+        #[test]
+        fn tabs() {
+                assert_eq!(
+                        render("\ta\tb", 3..4),
+                        "\
+error: oops
+ --> 1:4
+  |
+1 | \ta\tb
+  | \t \t^
+"
+                );
+        }
+
+        #[test]
+        fn crlf() {
+                assert_eq!(
+                        render("ab\r\ncd", 1..4),
+                        "\
+error: oops
+ --> 1:2
+  |
+1 | ab
+  |  ^^
+"
+                );
+                // the `\n` of a `\r\n` ends the line, not the `\r`
+                assert_eq!(
+                        render("ab\r\ncd", 4..5),
+                        "\
+error: oops
+ --> 2:1
+  |
+2 | cd
+  | ^
+"
+                );
+        }
+
+        #[test]
+        fn end_of_input() {
+                assert_eq!(
+                        render("ab", 2..2),
+                        "\
+error: oops
+ --> 1:3
+  |
+1 | ab
+  |   ^
+"
+                );
+                // the end of input is on the empty line after the last newline
+                assert_eq!(
+                        render("ab\n", 3..3),
+                        "\
+error: oops
+ --> 2:1
+  |
+2 | \n  | ^
+"
+                );
+                // a span past the end is cut off at it, so it doesn't reach that empty line
+                assert_eq!(
+                        render("ab\n", 1..10),
+                        "\
+error: oops
+ --> 1:2
+  |
+1 | ab
+  |  ^^
+"
+                );
+        }
+
+        #[test]
+        fn offset_inside_a_char() {
+                // 2 is inside the 'é', so the span starts at it
+                assert_eq!(
+                        render("a\u{e9}", 2..3),
+                        "\
+error: oops
+ --> 1:2
+  |
+1 | a\u{e9}
+  |  ^
+"
+                );
+        }
+
+        #[test]
+        fn render_all() {
+                let errors = [
+                        Rich::<&str>::custom(0..1, "first"),
+                        Rich::<&str>::custom(1..2, "second"),
+                ];
+                assert_eq!(
+                        Report::new("ab").render_all(&errors),
+                        "\
+error: first
+ --> 1:1
+  |
+1 | ab
+  | ^
+
+error: second
+ --> 1:2
+  |
+1 | ab
+  |  ^
+"
+                );
+        }
+
+        #[test]
+        fn color() {
+                let error = Rich::<&str>::custom(0..1, "oops");
+                assert_eq!(
+                        Report::new("ab").with_color(true).render(&error),
+                        "\x1b[1;31merror\x1b[0m: \x1b[1moops\x1b[0m
+ \x1b[1;34m-->\x1b[0m 1:1
+  \x1b[1;34m|\x1b[0m
+\x1b[1;34m1 |\x1b[0m ab
+  \x1b[1;34m|\x1b[0m \x1b[1;31m^\x1b[0m
+"
+                );
+        }
+}
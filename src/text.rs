@@ -3,8 +3,8 @@ use std::{borrow::Borrow, marker::PhantomData};
 use crate::{
         container::OrderedSeq,
         derive::parser,
-        error::{Error, LabelError},
-        input::{Input, InputType, StrInput},
+        error::{Error, LabelError, Suggestion},
+        input::{Input, InputType, StrInput, NEWLINE_CHARACTERS_AFTER_CRLF},
         parser::ParserExtras,
         pfn_type,
         prelude::Parser,
@@ -169,6 +169,58 @@ impl Char for u8 {
         }
 }
 
+/// The error of a keyword parser that found the identifier `found` at `span` instead of `keyword`.
+///
+/// If `found` looks like a misspelling of `keyword`, the error suggests replacing it with `keyword`.
+fn keyword_error<I: InputType, C: Char, E: ParserExtras<I>>(
+        input: &Input<I, E>,
+        before: I::Offset,
+        found: &C::Str,
+        keyword: &C::Str,
+) -> E::Error
+where
+        E::Error: LabelError<I, CharLabel<C>>,
+{
+        let error: E::Error = LabelError::from_label(
+                input.span_since(before),
+                CharLabel::ExpectedKeyword(C::owned(keyword)),
+                input.current(),
+        );
+
+        let found: Vec<C> = C::str_to_chars(found).collect();
+        let keyword: Vec<C> = C::str_to_chars(keyword).collect();
+        // like rustc, allow one edit for every three characters
+        if edit_distance(&found, &keyword) > (keyword.len() / 3).max(1) {
+                return error;
+        }
+        error.suggest(Suggestion::Replace {
+                span: input.span_since(before),
+                with: keyword.iter().map(Char::to_char).collect(),
+        })
+}
+
+/// The optimal string alignment distance between `a` and `b`: how many insertions, deletions, substitutions
+/// and transpositions of adjacent characters it takes to turn one into the other.
+fn edit_distance<T: PartialEq>(a: &[T], b: &[T]) -> usize {
+        // the last three rows of the distance matrix
+        let mut before_previous = vec![0; b.len() + 1];
+        let mut previous: Vec<usize> = (0..=b.len()).collect();
+        let mut current = vec![0; b.len() + 1];
+        for i in 1..=a.len() {
+                current[0] = i;
+                for j in 1..=b.len() {
+                        let substitution = previous[j - 1] + usize::from(a[i - 1] != b[j - 1]);
+                        current[j] = substitution.min(previous[j] + 1).min(current[j - 1] + 1);
+                        if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                                current[j] = current[j].min(before_previous[j - 2] + 1);
+                        }
+                }
+                std::mem::swap(&mut before_previous, &mut previous);
+                std::mem::swap(&mut previous, &mut current);
+        }
+        previous[b.len()]
+}
+
 pub mod ascii {
         use crate::error::LabelError;
 
@@ -209,6 +261,9 @@ pub mod ascii {
                 Ok(inp.input.slice(inp.span_since(before)))
         }
 
+        /// Like [`unicode::keyword`], a misspelling of the keyword is
+        /// reported with a suggestion, which only error types like [`Rich`](crate::extra::Rich) keep.
+        ///
         /// # Panics
         /// This function panics (only in debug mode) if the `keyword` is an invalid ASCII identifier.
         #[track_caller]
@@ -241,27 +296,13 @@ pub mod ascii {
                         let before = input.offset;
                         let ident = ident(input)?;
                         if ident != keyword.as_ref() {
-                                let span = input.span_since(before);
-                                return Err(LabelError::from_label(
-                                        span,
-                                        CharLabel::ExpectedKeyword(C::owned(keyword)),
-                                        input.current(),
-                                ));
+                                return Err(keyword_error(input, before, ident, keyword));
                         }
                         Ok(input.input.slice(input.span_since(before)))
                 }
         }
 }
 
-static NEWLINE_CHARACTERS_AFTER_CRLF: [char; 6] = [
-        '\r',       // Carriage return
-        '\x0B',     // Vertical tab
-        '\x0C',     // Form feed
-        '\u{0085}', // Next line
-        '\u{2028}', // Line separator
-        '\u{2029}', // Paragraph separator
-];
-
 /// A parser that accepts (and ignores) any newline characters or character sequences.
 ///
 /// The output type of this parser is `()`.
@@ -435,6 +476,27 @@ pub mod unicode {
         /// // 'def' was found, but only as part of a larger identifier, so this fails to parse
         /// assert!(def.parse("define").is_err());
         /// ```
+        ///
+        /// When the identifier that was found instead looks like a misspelling of the keyword,
+        /// the error suggests the keyword (see [`Error::suggest`](crate::error::Error::suggest)).
+        /// Only error types that keep suggestions, like [`Rich`](crate::extra::Rich), carry it;
+        /// [`Simple`](crate::extra::Simple) discards it:
+        ///
+        /// ```
+        /// # use aott::prelude::*;
+        /// use aott::{error::Suggestion, extra::Rich};
+        /// let r#while = text::unicode::keyword::<_, _, extra::Err<&str, Rich<&str>>>("while");
+        ///
+        /// let error = r#while.parse("whlie").unwrap_err();
+        /// assert_eq!(
+        ///     error.suggestions(),
+        ///     &[Suggestion::Replace { span: 0..5, with: "while".to_owned() }]
+        /// );
+        /// assert_eq!(error.suggestions()[0].to_string(), "did you mean `while`?");
+        ///
+        /// // but not when it's something else entirely
+        /// assert!(r#while.parse("loop").unwrap_err().suggestions().is_empty());
+        /// ```
         #[track_caller]
         pub fn keyword<
                 'a,
@@ -464,14 +526,9 @@ pub mod unicode {
                 move |input| {
                         let befunge = input.offset;
                         let s = ident::<I, C, E>(input)?;
-                        let span = input.span_since(befunge);
-                        (s == keyword.as_ref()).then_some(s).ok_or_else(|| {
-                                LabelError::from_label(
-                                        span,
-                                        CharLabel::ExpectedKeyword(C::owned(keyword)),
-                                        input.current(),
-                                )
-                        })
+                        (s == keyword)
+                                .then_some(s)
+                                .ok_or_else(|| keyword_error(input, befunge, s, keyword))
                 }
         }
 }
@@ -531,8 +588,8 @@ where
 {
         move |input| {
                 with_slice(input, move |input| {
-                        let cr = input.next()?;
                         let befunge = input.offset;
+                        let cr = input.next()?;
                         if !(cr.is_digit(radix) && cr != C::digit_zero()) {
                                 return Err(LabelError::from_label(
                                         input.span_since(befunge),
@@ -650,3 +707,47 @@ where
         .ignored()
         .repeated()
 }
+
+#[cfg(test)]
+mod test {
+        use crate::error::Suggestion;
+        use crate::extra::{Rich, Simple};
+        use crate::prelude::*;
+        use crate::text::CharLabel;
+
+        #[test]
+        fn rich_keeps_keyword_suggestion() {
+                let suggestion = [Suggestion::Replace {
+                        span: 0..5,
+                        with: "while".to_owned(),
+                }];
+
+                let ascii = text::ascii::keyword::<_, _, extra::Err<&str, Rich<&str>>>("while");
+                assert_eq!(ascii.parse("whlie").unwrap_err().suggestions(), &suggestion);
+
+                let unicode = text::unicode::keyword::<_, _, extra::Err<&str, Rich<&str>>>("while");
+                assert_eq!(
+                        unicode.parse("whlie").unwrap_err().suggestions(),
+                        &suggestion
+                );
+        }
+
+        #[test]
+        fn simple_drops_keyword_suggestion() {
+                let ascii = text::ascii::keyword::<_, _, extra::Err<&str>>("while");
+                let unicode = text::unicode::keyword::<_, _, extra::Err<&str>>("while");
+
+                for error in [
+                        ascii.parse("whlie").unwrap_err(),
+                        unicode.parse("whlie").unwrap_err(),
+                ] {
+                        assert!(matches!(
+                                error,
+                                Simple::Text {
+                                        error: CharLabel::ExpectedKeyword(..),
+                                        ..
+                                }
+                        ));
+                }
+        }
+}